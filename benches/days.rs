@@ -0,0 +1,58 @@
+use advent_of_code_2022_rust::generate::generate;
+use advent_of_code_2022_rust::input::{load_input, InputSource};
+use advent_of_code_2022_rust::solution::Solution;
+use advent_of_code_2022_rust::{
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::path::Path;
+
+fn real_input(day: u8) -> String {
+    let inputs_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+    return load_input(day, &InputSource::Default, &inputs_dir).expect("Unable to load input");
+}
+
+fn bench_day<S: Solution>(c: &mut Criterion, inputs: &[(&str, String)]) {
+    let mut group = c.benchmark_group(format!("day_{:02}", S::DAY));
+    group.sample_size(20);
+
+    for (name, input) in inputs {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("parse", name), input, |b, input| {
+            b.iter(|| S::parse(black_box(input)))
+        });
+
+        let parsed = S::parse(input).expect("Unable to parse input");
+        group.bench_with_input(BenchmarkId::new("part_1", name), &parsed, |b, parsed| {
+            b.iter(|| S::part_1(black_box(parsed)))
+        });
+        group.bench_with_input(BenchmarkId::new("part_2", name), &parsed, |b, parsed| {
+            b.iter(|| S::part_2(black_box(parsed)))
+        });
+    }
+
+    group.finish();
+}
+
+/** Benchmarks the real input, and a large generated one. */
+fn bench_with_large_input<S: Solution>(c: &mut Criterion, size: usize) {
+    let real = real_input(S::DAY);
+    let large = generate(S::DAY, size, 0).expect("Unable to generate input");
+    bench_day::<S>(c, &[("real", real), ("large", large)]);
+}
+
+fn bench_days(c: &mut Criterion) {
+    bench_with_large_input::<day_01::Day01>(c, 20_000);
+    bench_with_large_input::<day_02::Day02>(c, 100_000);
+    bench_with_large_input::<day_03::Day03>(c, 30_000);
+    bench_with_large_input::<day_04::Day04>(c, 100_000);
+    bench_with_large_input::<day_05::Day05>(c, 50_000);
+    bench_with_large_input::<day_06::Day06>(c, 1_000_000);
+    bench_with_large_input::<day_07::Day07>(c, 20_000);
+    // The day 8 algorithms are far from linear
+    bench_with_large_input::<day_08::Day08>(c, 300);
+    bench_with_large_input::<day_09::Day09>(c, 20_000);
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
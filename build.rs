@@ -0,0 +1,80 @@
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/** The day number of a `day_NN.rs` file name. */
+fn day_number(file_name: &str) -> Option<u8> {
+    let number = file_name.strip_prefix("day_")?.strip_suffix(".rs")?;
+    if number.len() != 2 {
+        return None;
+    }
+    return number.parse().ok();
+}
+
+/** SHA-256 of the names and contents of the source files and of the manifest. */
+fn source_hash(manifest_dir: &Path, src_dir: &Path) -> String {
+    let mut paths = std::fs::read_dir(src_dir)
+        .expect("Unable to list the source files")
+        .map(|entry| entry.expect("Unable to list the source files").path())
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+    paths.push(manifest_dir.join("Cargo.toml"));
+
+    let mut hasher = Sha256::new();
+    for path in paths {
+        hasher.update(path.file_name().unwrap().as_encoded_bytes());
+        hasher.update(std::fs::read(&path).expect("Unable to read a source file"));
+    }
+    return hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+}
+
+/**
+ * Registers every `src/day_NN.rs` module, so adding a day doesn't need any other change.
+ * Writes the module declarations and the list of days of the registry,
+ * both included by the library. Every module has a `pub fn register() -> Day`,
+ * which says whether the day can be solved while streaming its input.
+ * Also hashes the sources and gives the profile, so the cached answers are invalidated
+ * when they change.
+ */
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let src_dir = Path::new(&manifest_dir).join("src");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!(
+        "cargo:rustc-env=SOURCE_HASH={}",
+        source_hash(Path::new(&manifest_dir), &src_dir)
+    );
+    // The timings of a debug build are no use to a release build
+    println!(
+        "cargo:rustc-env=BUILD_PROFILE={}",
+        std::env::var("PROFILE").unwrap()
+    );
+
+    let mut days = std::fs::read_dir(&src_dir)
+        .expect("Unable to list the source files")
+        .filter_map(|entry| {
+            let path = entry.expect("Unable to list the source files").path();
+            let day = day_number(path.file_name()?.to_str()?)?;
+            return Some((day, path));
+        })
+        .collect::<Vec<(u8, PathBuf)>>();
+    days.sort();
+
+    let mut modules = String::new();
+    let mut registry = String::from("vec![\n");
+    for (day, path) in days {
+        writeln!(modules, "#[path = {:?}]", path.display().to_string()).unwrap();
+        writeln!(modules, "pub mod day_{:02};", day).unwrap();
+        writeln!(registry, "    crate::day_{:02}::register(),", day).unwrap();
+    }
+    registry.push(']');
+
+    std::fs::write(out_dir.join("days.rs"), modules).expect("Unable to write the day modules");
+    std::fs::write(out_dir.join("registry.rs"), registry).expect("Unable to write the registry");
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code_2022_rust::day_07;
+use libfuzzer_sys::fuzz_target;
+
+// Invalid inputs must be reported as errors, never panic
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = day_07::parse_input_data(input);
+    }
+});
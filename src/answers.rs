@@ -0,0 +1,394 @@
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::input::InputSource;
+use crate::registry;
+use crate::runner::{self, DayRun};
+use crate::solution::Answer;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+pub const DEFAULT_EXAMPLES_DIR: &str = "inputs/examples";
+
+/** Expected answers of the parts of a day, or of an example. */
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part_1: Option<Answer>,
+    part_2: Option<Answer>,
+}
+
+impl DayAnswers {
+    fn get(&self, part: u8) -> Option<&Answer> {
+        return match part {
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        };
+    }
+}
+
+fn read(path: &Path) -> Result<String, AnswersError> {
+    return std::fs::read_to_string(path).map_err(|error| AnswersError::Io {
+        path: path.to_path_buf(),
+        error,
+    });
+}
+
+fn parse_tables(content: &str) -> Result<HashMap<String, DayAnswers>, AnswersError> {
+    return toml::from_str(content).map_err(AnswersError::Toml);
+}
+
+/** The day of a name like day_NN, or like day_NN_something when `suffix` is set. */
+fn day_of(name: &str, suffix: bool) -> Option<u8> {
+    let name = name.strip_prefix("day_")?;
+    let day = match suffix {
+        true => name.split_once('_')?.0,
+        false => name,
+    };
+    return day.parse().ok();
+}
+
+/**
+ * Known correct answers, stored in a TOML file such as:
+ *
+ * ```toml
+ * [day_05]
+ * part_1 = "CMZ"
+ * part_2 = "MCD"
+ * ```
+ */
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Toml(toml::de::Error),
+    InvalidDay(String),
+    /** An example file without answers, so it would never be checked */
+    MissingAnswers(PathBuf),
+    /** Answers of an example file that doesn't exist */
+    MissingExample(String),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io { path, error } => {
+                write!(f, "Unable to read {}: {}", path.display(), error)
+            }
+            AnswersError::Toml(error) => write!(f, "Invalid answers file: {}", error),
+            AnswersError::InvalidDay(key) => {
+                write!(f, "Invalid answers file: \"{}\" is not like day_NN", key)
+            }
+            AnswersError::MissingAnswers(path) => {
+                write!(f, "The example {} has no expected answers", path.display())
+            }
+            AnswersError::MissingExample(name) => {
+                write!(
+                    f,
+                    "There are answers for {} but no {}.txt example",
+                    name, name
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+impl ExpectedAnswers {
+    pub fn parse(content: &str) -> Result<ExpectedAnswers, AnswersError> {
+        let mut days = BTreeMap::new();
+        for (key, answers) in parse_tables(content)? {
+            let day = day_of(&key, false).ok_or_else(|| AnswersError::InvalidDay(key.clone()))?;
+            days.insert(day, answers);
+        }
+        return Ok(ExpectedAnswers { days });
+    }
+
+    pub fn load(path: &Path) -> Result<ExpectedAnswers, AnswersError> {
+        return ExpectedAnswers::parse(&read(path)?);
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        return self.days.get(&day)?.get(part);
+    }
+}
+
+/** An example input from the puzzle text, with the answers given there. */
+#[derive(Debug)]
+pub struct Example {
+    pub day: u8,
+    /** The file name without its extension, such as day_07_example */
+    pub name: String,
+    pub path: PathBuf,
+    answers: DayAnswers,
+}
+
+impl Example {
+    pub fn get(&self, part: u8) -> Option<&Answer> {
+        return self.answers.get(part);
+    }
+
+    /** Only the parts with an answer are meant to be run on the example */
+    pub fn parts(&self) -> Vec<u8> {
+        return [1, 2]
+            .into_iter()
+            .filter(|part| self.get(*part).is_some())
+            .collect();
+    }
+}
+
+/**
+ * Finds the day_NN_*.txt examples of a folder, sorted by name.
+ * Their answers are in the answers.toml file of the same folder, in the format of
+ * the expected answers, but keyed by the name of the example.
+ */
+pub fn load_examples(examples_dir: &Path) -> Result<Vec<Example>, AnswersError> {
+    let mut answers = parse_tables(&read(&examples_dir.join(DEFAULT_ANSWERS_FILE))?)?;
+
+    let entries = std::fs::read_dir(examples_dir).map_err(|error| AnswersError::Io {
+        path: examples_dir.to_path_buf(),
+        error,
+    })?;
+    let mut examples = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|error| AnswersError::Io {
+                path: examples_dir.to_path_buf(),
+                error,
+            })?
+            .path();
+        if path.extension().is_none_or(|extension| extension != "txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let day = day_of(&name, true).ok_or_else(|| AnswersError::InvalidDay(name.clone()))?;
+        let example_answers = answers
+            .remove(&name)
+            .ok_or_else(|| AnswersError::MissingAnswers(path.clone()))?;
+        examples.push(Example {
+            day,
+            name,
+            path,
+            answers: example_answers,
+        });
+    }
+
+    if let Some(name) = answers.into_keys().min() {
+        return Err(AnswersError::MissingExample(name));
+    }
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    return Ok(examples);
+}
+
+/** An answer compared with the expected one. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: Answer,
+        answer: Answer,
+    },
+    /** There is nothing to compare with, which isn't a failure */
+    Unknown(Answer),
+}
+
+impl Verdict {
+    pub fn new(expected: Option<&Answer>, answer: &Answer) -> Verdict {
+        return match expected {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+                answer: answer.clone(),
+            },
+            None => Verdict::Unknown(answer.clone()),
+        };
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected, answer } => {
+                write!(f, "FAIL, expected {} but got {}", expected, answer)
+            }
+            Verdict::Unknown(answer) => write!(f, "no expected answer, got {}", answer),
+        }
+    }
+}
+
+/** The verdicts of the parts of a day or an example, or the error preventing them. */
+#[derive(Debug)]
+pub struct Verification {
+    /** Day N, or the name of the example */
+    pub name: String,
+    pub parts: Result<Vec<(u8, Verdict)>, Error>,
+}
+
+impl Verification {
+    fn new<'a>(
+        name: String,
+        solved: Result<DayRun, Error>,
+        expected: impl Fn(u8) -> Option<&'a Answer>,
+    ) -> Verification {
+        let parts = solved.map(|run| {
+            run.parts
+                .iter()
+                .map(|part| (part.part, Verdict::new(expected(part.part), &part.answer)))
+                .collect()
+        });
+        return Verification { name, parts };
+    }
+
+    /** A day or an example that couldn't be solved counts as a single failure. */
+    pub fn failures(&self) -> usize {
+        return match &self.parts {
+            Ok(parts) => parts
+                .iter()
+                .filter(|(_, verdict)| matches!(verdict, Verdict::Fail { .. }))
+                .count(),
+            Err(_) => 1,
+        };
+    }
+}
+
+/** Solves both parts of every day on its default input, on up to `jobs` threads. */
+pub fn verify_inputs(
+    expected: &ExpectedAnswers,
+    inputs_dir: &Path,
+    jobs: usize,
+    cache: Option<&Cache>,
+) -> Vec<Verification> {
+    let days = registry::days();
+    let solved = runner::solve_parallel(
+        &days,
+        jobs,
+        |day| day.day,
+        |day| runner::solve_day(day, &InputSource::Default, inputs_dir, &[1, 2], 1, cache),
+    );
+    return days
+        .iter()
+        .zip(solved)
+        .map(|(day, solved)| {
+            Verification::new(format!("Day {}", day.day), solved, |part| {
+                expected.get(day.day, part)
+            })
+        })
+        .collect();
+}
+
+/** Solves the parts with an answer of every example, on up to `jobs` threads. */
+pub fn verify_examples(
+    examples: &[Example],
+    examples_dir: &Path,
+    jobs: usize,
+    cache: Option<&Cache>,
+) -> Vec<Verification> {
+    let solved = runner::solve_parallel(
+        examples,
+        jobs,
+        |example| example.day,
+        |example| {
+            let day = registry::find(example.day).ok_or(Error::NotImplemented(example.day))?;
+            let source = InputSource::Path(example.path.clone());
+            return runner::solve_day(&day, &source, examples_dir, &example.parts(), 1, cache);
+        },
+    );
+    return examples
+        .iter()
+        .zip(solved)
+        .map(|(example, solved)| {
+            Verification::new(example.name.clone(), solved, |part| example.get(part))
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = ExpectedAnswers::parse(
+            "[day_04]
+part_1 = 2
+part_2 = 4
+
+[day_05]
+part_1 = \"CMZ\"",
+        )
+        .unwrap();
+
+        assert_eq!(answers.get(4, 1), Some(&Answer::Integer(2)));
+        assert_eq!(answers.get(4, 2), Some(&Answer::Integer(4)));
+        assert_eq!(answers.get(5, 1), Some(&Answer::Text("CMZ".to_string())));
+        assert_eq!(answers.get(5, 2), None);
+        assert_eq!(answers.get(6, 1), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(
+            ExpectedAnswers::parse("[monday]\npart_1 = 1"),
+            Err(AnswersError::InvalidDay(_))
+        ));
+        assert!(matches!(
+            ExpectedAnswers::parse("[day_01]\npart_3 = 1"),
+            Err(AnswersError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn test_verdict() {
+        let answer = Answer::Integer(4);
+        assert_eq!(Verdict::new(Some(&answer), &answer), Verdict::Pass);
+        let verdict = Verdict::new(Some(&Answer::Integer(2)), &answer);
+        assert_eq!(verdict.to_string(), "FAIL, expected 2 but got 4");
+        let verdict = Verdict::new(None, &answer);
+        assert_eq!(verdict.to_string(), "no expected answer, got 4");
+
+        let verification = Verification {
+            name: "Day 4".to_string(),
+            parts: Ok(vec![(1, Verdict::Pass), (2, verdict)]),
+        };
+        assert_eq!(verification.failures(), 0);
+        let verification = Verification {
+            name: "Day 4".to_string(),
+            parts: Err(Error::NotImplemented(4)),
+        };
+        assert_eq!(verification.failures(), 1);
+    }
+
+    #[test]
+    fn test_day_of() {
+        assert_eq!(day_of("day_07", false), Some(7));
+        assert_eq!(day_of("day_07_example", false), None);
+        assert_eq!(day_of("day_07_example", true), Some(7));
+        assert_eq!(day_of("day_7", false), Some(7));
+        assert_eq!(day_of("day_07", true), None);
+    }
+
+    #[test]
+    fn test_load_examples() {
+        let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_EXAMPLES_DIR);
+        let examples = load_examples(&examples_dir).unwrap();
+        let day_7 = examples
+            .iter()
+            .find(|example| example.name == "day_07_example_2")
+            .unwrap();
+        assert_eq!(day_7.day, 7);
+        assert_eq!(day_7.parts(), vec![1]);
+        assert_eq!(day_7.get(1), Some(&Answer::Integer(2042)));
+    }
+}
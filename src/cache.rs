@@ -0,0 +1,280 @@
+use crate::runner::{DayRun, PartRun, Timing};
+use crate::solution::Answer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_CACHE_FILE: &str = "target/answers_cache.json";
+
+/**
+ * Identifies the solutions, as the answers can change with any source file
+ * and the timings with the profile. Both are given by build.rs.
+ */
+pub const SOLUTIONS_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "+",
+    env!("SOURCE_HASH"),
+    ".",
+    env!("BUILD_PROFILE")
+);
+
+/** SHA-256 of an input, in hexadecimal. */
+pub fn input_hash(input: &str) -> String {
+    return Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+}
+
+/** The answer of a part and its timings, for an input and a version of the solutions. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    day: u8,
+    part: u8,
+    input_sha256: String,
+    version: String,
+    answer: Answer,
+    /** The parsing done for this part, in nanoseconds */
+    parse_ns: Vec<u64>,
+    part_ns: Vec<u64>,
+}
+
+fn to_nanoseconds(timing: &Timing) -> Vec<u64> {
+    return timing
+        .samples
+        .iter()
+        .map(|sample| sample.as_nanos() as u64)
+        .collect();
+}
+
+fn to_timing(nanoseconds: &[u64]) -> Timing {
+    return Timing {
+        samples: nanoseconds
+            .iter()
+            .map(|ns| Duration::from_nanos(*ns))
+            .collect(),
+    };
+}
+
+/** Day, part, input and number of runs, as the timings of more runs are different. */
+type Key = (u8, u8, String, usize);
+
+impl Entry {
+    fn key(&self) -> Key {
+        return (
+            self.day,
+            self.part,
+            self.input_sha256.clone(),
+            self.part_ns.len(),
+        );
+    }
+}
+
+/**
+ * Answers and timings of the previous runs, stored in a JSON file.
+ * The entries of other versions of the solutions are dropped when it's loaded.
+ * It can be shared between the threads solving the days.
+ */
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<Key, Entry>>,
+}
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Json {
+        path: PathBuf,
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::Io { path, error } => {
+                write!(
+                    f,
+                    "Unable to access the cache {}: {}",
+                    path.display(),
+                    error
+                )
+            }
+            CacheError::Json { path, error } => write!(
+                f,
+                "Invalid cache {}, it can be deleted: {}",
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl Cache {
+    /** A missing file is an empty cache. */
+    pub fn load(path: &Path) -> Result<Cache, CacheError> {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(content) => {
+                serde_json::from_str::<Vec<Entry>>(&content).map_err(|error| CacheError::Json {
+                    path: path.to_path_buf(),
+                    error,
+                })?
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                return Err(CacheError::Io {
+                    path: path.to_path_buf(),
+                    error,
+                })
+            }
+        };
+
+        return Ok(Cache {
+            path: path.to_path_buf(),
+            entries: Mutex::new(
+                entries
+                    .into_iter()
+                    .filter(|entry| entry.version == SOLUTIONS_VERSION)
+                    .map(|entry| (entry.key(), entry))
+                    .collect(),
+            ),
+        });
+    }
+
+    /**
+     * The cached run of a day, only if all the requested parts are cached
+     * with the same number of runs.
+     */
+    pub fn get(&self, day: u8, input_sha256: &str, parts: &[u8], repeat: usize) -> Option<DayRun> {
+        let entries = self.entries.lock().unwrap();
+        let cached = parts
+            .iter()
+            .map(|&part| entries.get(&(day, part, input_sha256.to_string(), repeat.max(1))))
+            .collect::<Option<Vec<&Entry>>>()?;
+
+        return Some(DayRun {
+            day,
+            parse: to_timing(&cached.first()?.parse_ns),
+            parts: cached
+                .iter()
+                .map(|entry| PartRun {
+                    part: entry.part,
+                    answer: entry.answer.clone(),
+                    timing: to_timing(&entry.part_ns),
+                })
+                .collect(),
+        });
+    }
+
+    pub fn insert(&self, input_sha256: &str, run: &DayRun) {
+        let mut entries = self.entries.lock().unwrap();
+        for part in &run.parts {
+            let entry = Entry {
+                day: run.day,
+                part: part.part,
+                input_sha256: input_sha256.to_string(),
+                version: SOLUTIONS_VERSION.to_string(),
+                answer: part.answer.clone(),
+                parse_ns: to_nanoseconds(&run.parse),
+                part_ns: to_nanoseconds(&part.timing),
+            };
+            entries.insert(entry.key(), entry);
+        }
+    }
+
+    pub fn save(&self) -> Result<(), CacheError> {
+        let io_error = |error| CacheError::Io {
+            path: self.path.clone(),
+            error,
+        };
+        let entries = self.entries.lock().unwrap();
+        let content = serde_json::to_string_pretty(&entries.values().collect::<Vec<&Entry>>())
+            .map_err(|error| CacheError::Json {
+                path: self.path.clone(),
+                error,
+            })?;
+
+        if let Some(folder) = self.path.parent() {
+            std::fs::create_dir_all(folder).map_err(io_error)?;
+        }
+        return std::fs::write(&self.path, content).map_err(io_error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run() -> DayRun {
+        return DayRun {
+            day: 5,
+            parse: to_timing(&[3, 1]),
+            parts: vec![
+                PartRun {
+                    part: 1,
+                    answer: Answer::Text("CMZ".to_string()),
+                    timing: to_timing(&[10, 20]),
+                },
+                PartRun {
+                    part: 2,
+                    answer: Answer::Text("MCD".to_string()),
+                    timing: to_timing(&[30, 40]),
+                },
+            ],
+        };
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(
+            input_hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let path = std::env::temp_dir()
+            .join(format!("aoc_2022_cache_{}", std::process::id()))
+            .join("cache.json");
+        let cache = Cache::load(&path).unwrap();
+        assert!(cache.get(5, "abc", &[1], 2).is_none());
+
+        cache.insert("abc", &run());
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path).unwrap();
+        let cached = cache.get(5, "abc", &[2, 1], 2).unwrap();
+        assert_eq!(cached.day, 5);
+        assert_eq!(cached.parse.samples, run().parse.samples);
+        assert_eq!(cached.parts[0].answer, Answer::Text("MCD".to_string()));
+        assert_eq!(
+            cached.parts[1].timing.samples,
+            run().parts[0].timing.samples
+        );
+        assert!(cache.get(5, "other input", &[1], 2).is_none());
+        assert!(cache.get(5, "abc", &[1, 3], 2).is_none());
+        // Not timed as many times
+        assert!(cache.get(5, "abc", &[1], 1).is_none());
+        assert!(cache.get(5, "abc", &[1], 20).is_none());
+
+        // Another version of the solutions
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace(SOLUTIONS_VERSION, "0.0.0+old")).unwrap();
+        assert!(Cache::load(&path).unwrap().get(5, "abc", &[1], 2).is_none());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(Cache::load(&path), Err(CacheError::Json { .. })));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+use crate::answers::{DEFAULT_ANSWERS_FILE, DEFAULT_EXAMPLES_DIR};
+use crate::cache::DEFAULT_CACHE_FILE;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+pub const DEFAULT_SRC_DIR: &str = "src";
+pub const DEFAULT_REPEAT: usize = 10;
+
+#[derive(Debug, Parser)]
+#[command(about = "Advent of code 2022 solutions", version)]
+pub struct Cli {
+    /** Defaults to running every day */
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /** Run the solutions of some days */
+    Run(RunArgs),
+    /** Run every day and compare the answers with the known correct ones */
+    Verify(VerifyArgs),
+    /** Write a random but valid input for a day */
+    Generate(GenerateArgs),
+    /** Run a day again every time its input or example file changes */
+    Watch(WatchArgs),
+    /** Create the module and the empty input file of a new day */
+    NewDay(NewDayArgs),
+    /** Statistics of the calories carried by the elves of day 1 */
+    Stats(StatsArgs),
+    /** Move the day 1 items between the elves, so the biggest total is as small as possible */
+    Rebalance(RebalanceArgs),
+}
+
+#[derive(Debug, Args, Default)]
+#[group(required = true, multiple = false)]
+pub struct DaySelection {
+    /** Day to run, or an inclusive range of days such as 3..6 */
+    #[arg(long, value_parser = parse_day_range)]
+    pub day: Option<RangeInclusive<u8>>,
+
+    /** Run every registered day */
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub days: DaySelection,
+
+    /** Only run this part */
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /** Input file of the selected day, or - to read it from stdin */
+    #[arg(long)]
+    pub input: Option<String>,
+
+    /** Folder containing the day_NN.txt input files */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** Measure parsing and both parts, and print a timing report */
+    #[arg(long)]
+    pub time: bool,
+
+    /** Number of runs the timings are computed from [default: 10 with --time, 1 otherwise] */
+    #[arg(long)]
+    pub repeat: Option<usize>,
+
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /** Solve while reading the input, for inputs too large to fit in memory */
+    #[arg(long, conflicts_with_all = ["repeat", "cached"])]
+    pub stream: bool,
+
+    /** Number of days solved at the same time, the results are still printed in order */
+    #[arg(long, default_value_t = NonZeroUsize::MIN)]
+    pub jobs: NonZeroUsize,
+
+    #[command(flatten)]
+    pub cache: CacheArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    /** Reuse the answers and timings of a previous run on the same input and the same build */
+    #[arg(long)]
+    pub cached: bool,
+
+    /** File where the answers are cached */
+    #[arg(long, default_value = DEFAULT_CACHE_FILE)]
+    pub cache_file: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /** Human readable answers */
+    Text,
+    /** One object per answered part, with the timings in nanoseconds */
+    Json,
+    /** Same fields as the JSON format */
+    Csv,
+}
+
+impl RunArgs {
+    pub fn repeat(&self) -> usize {
+        return match self.repeat {
+            Some(repeat) => repeat,
+            None if self.time => DEFAULT_REPEAT,
+            // Without timing report, there is no point running the days more than once
+            None => 1,
+        };
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /** TOML file with the expected answers of every day */
+    #[arg(long, default_value = DEFAULT_ANSWERS_FILE)]
+    pub answers: PathBuf,
+
+    /** Folder containing the day_NN.txt input files */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** Number of days solved at the same time */
+    #[arg(long, default_value_t = NonZeroUsize::MIN)]
+    pub jobs: NonZeroUsize,
+
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /** Check the examples of the puzzles instead of the real inputs */
+    #[arg(long)]
+    pub examples: bool,
+
+    /** Folder containing the day_NN_*.txt examples, and their answers.toml */
+    #[arg(long, default_value = DEFAULT_EXAMPLES_DIR)]
+    pub examples_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    #[arg(long)]
+    pub day: u8,
+
+    /** Number of elves, games, rucksacks, pairs, moves, characters, entries, trees per side or motions */
+    #[arg(long, default_value_t = 1000)]
+    pub size: usize,
+
+    /** The same seed always generates the same input */
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /** Write the input to this file instead of stdout */
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[arg(long)]
+    pub day: u8,
+
+    /** Also run the day on this example file, and watch it too */
+    #[arg(long)]
+    pub example: Option<PathBuf>,
+
+    /** Folder containing the day_NN.txt input files */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** How often the files are checked for changes, in milliseconds */
+    #[arg(long, default_value_t = 500)]
+    pub interval: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct NewDayArgs {
+    pub day: u8,
+
+    /** Folder where the day_NN.rs module is created */
+    #[arg(long, default_value = DEFAULT_SRC_DIR)]
+    pub src_dir: PathBuf,
+
+    /** Folder where the empty day_NN.txt input file is created */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** Folder where the day_NN_example.txt example is created, and its answers added */
+    #[arg(long, default_value = DEFAULT_EXAMPLES_DIR)]
+    pub examples_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /** Input file of day 1, or - to read it from stdin */
+    #[arg(long)]
+    pub input: Option<String>,
+
+    /** Folder containing the day_NN.txt input files */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** Maximum number of buckets of the histogram of the totals */
+    #[arg(long, default_value_t = NonZeroUsize::new(10).unwrap())]
+    pub buckets: NonZeroUsize,
+
+    /** The CSV format only has the statistics of every elf */
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(Debug, Args)]
+pub struct RebalanceArgs {
+    /** Input file of day 1, or - to read it from stdin */
+    #[arg(long)]
+    pub input: Option<String>,
+
+    /** Folder containing the day_NN.txt input files */
+    #[arg(long, default_value = DEFAULT_INPUTS_DIR)]
+    pub inputs_dir: PathBuf,
+
+    /** Number of elves carrying the items [default: as many as in the input] */
+    #[arg(long)]
+    pub elves: Option<NonZeroUsize>,
+
+    /** The CSV format has a line per item */
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+impl Default for CacheArgs {
+    fn default() -> Self {
+        CacheArgs {
+            cached: false,
+            cache_file: PathBuf::from(DEFAULT_CACHE_FILE),
+        }
+    }
+}
+
+impl Default for RunArgs {
+    fn default() -> Self {
+        RunArgs {
+            days: DaySelection::default(),
+            part: None,
+            input: None,
+            inputs_dir: PathBuf::from(DEFAULT_INPUTS_DIR),
+            time: false,
+            repeat: None,
+            format: Format::Text,
+            stream: false,
+            jobs: NonZeroUsize::MIN,
+            cache: CacheArgs::default(),
+        }
+    }
+}
+
+/** Parses "7", "3..6" or "3..=6" into an inclusive range of days. */
+pub fn parse_day_range(input: &str) -> Result<RangeInclusive<u8>, String> {
+    let parse_day = |day: &str| {
+        day.trim()
+            .parse::<u8>()
+            .map_err(|_| format!("\"{}\" is not a day number", day))
+    };
+
+    let range = match input.split_once("..") {
+        Some((start, end)) => {
+            let end = end.strip_prefix('=').unwrap_or(end);
+            parse_day(start)?..=parse_day(end)?
+        }
+        None => {
+            let day = parse_day(input)?;
+            day..=day
+        }
+    };
+
+    if range.is_empty() {
+        return Err(format!("\"{}\" is an empty range of days", input));
+    }
+    return Ok(range);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day_range() {
+        assert_eq!(parse_day_range("7"), Ok(7..=7));
+        assert_eq!(parse_day_range("3..6"), Ok(3..=6));
+        assert_eq!(parse_day_range("3..=6"), Ok(3..=6));
+        assert!(parse_day_range("6..3").is_err());
+        assert!(parse_day_range("three").is_err());
+        assert!(parse_day_range("3..").is_err());
+    }
+}
@@ -1,80 +1,662 @@
-pub fn parse_input_data(input: &str) -> Vec<Vec<i64>> {
-    return input
-        // Split the string every two end of line characters
-        .split("\n\n")
-        .map(|elf| {
-            return elf
-                // Split the string into lines
-                .lines()
-                // Convert each line to a vector of i64
-                .map(|line| line.parse::<i64>().expect("Failed to parse number"))
-                .collect();
-        })
-        .collect();
+use crate::error::{Error, ParseError};
+use crate::input::for_each_line;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
+use std::io::BufRead;
+use std::num::IntErrorKind;
+
+const DAY: u8 = 1;
+
+/** A number of calories, which can't be negative. */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Calories(i64);
+
+impl Calories {
+    pub const ZERO: Calories = Calories(0);
+
+    /** None for a negative number of calories */
+    pub fn new(calories: i64) -> Option<Calories> {
+        return (calories >= 0).then_some(Calories(calories));
+    }
+
+    pub fn get(self) -> i64 {
+        return self.0;
+    }
+
+    /** None when the sum doesn't fit */
+    pub fn checked_add(self, other: Calories) -> Option<Calories> {
+        return self.0.checked_add(other.0).map(Calories);
+    }
+}
+
+impl fmt::Display for Calories {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Like an integer, with the width and the alignment
+        fmt::Display::fmt(&self.0, f)
+    }
 }
 
-pub fn day_1_part_1(data: &str) -> i64 {
-    let data = parse_input_data(data);
+/**
+ * The calories carried by an elf. The parser checks that the calories of all the elves
+ * add up without overflowing, so only elves made by hand can make it panic.
+ */
+pub fn total(items: &[Calories]) -> Calories {
+    return items
+        .iter()
+        .try_fold(Calories::ZERO, |total, item| total.checked_add(*item))
+        .expect("The total of the calories overflows");
+}
+
+/** The calories of an item, or None for the empty line separating the elves. */
+fn parse_line(index: usize, line: &str) -> Result<Option<Calories>, ParseError> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let error = |message: &str| ParseError::new(DAY, index + 1, 1, line, message);
+    let calories = line.parse::<i64>().map_err(|parse_error| match parse_error.kind() {
+        IntErrorKind::PosOverflow => error("expected a number of calories that fits in 64 bits"),
+        IntErrorKind::NegOverflow => error("expected a positive number of calories"),
+        _ => error("expected a number of calories"),
+    })?;
+    let calories = Calories::new(calories)
+        .ok_or_else(|| error("expected a positive number of calories"))?;
+    return Ok(Some(calories));
+}
 
-    let sums = data.iter().map(|elf| {
-        return elf.iter().sum();
+/**
+ * Adds the calories of an item to the total of all the elves, so the total of every elf
+ * and of the top elves of the parts are known to fit. Other sums of totals can still
+ * overflow, like in `statistics`.
+ */
+fn add_to_total(
+    total: Calories,
+    calories: Calories,
+    index: usize,
+    line: &str,
+) -> Result<Calories, ParseError> {
+    return total.checked_add(calories).ok_or_else(|| {
+        ParseError::new(
+            DAY,
+            index + 1,
+            1,
+            line,
+            "expected fewer calories, the total of all the elves overflows",
+        )
     });
+}
+
+/**
+ * Empty lines separate the elves. There can be several of them between two elves,
+ * and before the first one or after the last one.
+ */
+pub fn parse_input_data(input: &str) -> Result<Vec<Vec<Calories>>, ParseError> {
+    let mut elves = Vec::new();
+    let mut elf = Vec::new();
+    let mut all_elves = Calories::ZERO;
+
+    for (index, line) in input.lines().enumerate() {
+        match parse_line(index, line)? {
+            Some(calories) => {
+                all_elves = add_to_total(all_elves, calories, index, line)?;
+                elf.push(calories);
+            }
+            None if !elf.is_empty() => elves.push(std::mem::take(&mut elf)),
+            None => {}
+        }
+    }
+    if !elf.is_empty() {
+        elves.push(elf);
+    }
+
+    return Ok(elves);
+}
+
+/** The elves carrying the most calories, from the biggest total, and the sum of their totals. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopCalories {
+    pub indices: Vec<usize>,
+    pub sum: Calories,
+}
+
+/**
+ * Keeps the K biggest totals of calories, given one elf at a time, so the elves don't
+ * have to be kept in memory. A min-heap of K elves makes it O(n log K).
+ * The first elves win the ties.
+ */
+#[derive(Debug)]
+pub struct TopK {
+    k: usize,
+    // The smallest total, and the last elf among the equal ones, on top
+    heap: BinaryHeap<Reverse<(Calories, Reverse<usize>)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> TopK {
+        return TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        };
+    }
+
+    pub fn push(&mut self, index: usize, calories: Calories) {
+        self.heap.push(Reverse((calories, Reverse(index))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    pub fn top(self) -> TopCalories {
+        let top = self.heap.into_sorted_vec();
+        return TopCalories {
+            indices: top
+                .iter()
+                .map(|Reverse((_, Reverse(index)))| *index)
+                .collect(),
+            sum: total(
+                &top.iter()
+                    .map(|Reverse((calories, _))| *calories)
+                    .collect::<Vec<Calories>>(),
+            ),
+        };
+    }
+}
+
+/** The K elves carrying the most calories. There are fewer when there are fewer elves. */
+pub fn top_k_calories(data: &[Vec<Calories>], k: usize) -> TopCalories {
+    let mut top_k = TopK::new(k);
+    for (index, elf) in data.iter().enumerate() {
+        top_k.push(index, total(elf));
+    }
+    return top_k.top();
+}
+
+/** The percentiles given by `statistics`, besides the median. */
+pub const PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElfStatistics {
+    /** Index of the elf in the input, starting at 0 */
+    pub elf: usize,
+    pub total: i64,
+    pub items: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Percentile {
+    pub percentile: u8,
+    pub total: i64,
+}
+
+/** Elves whose total is between `start` and `end`, both included. */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bucket {
+    pub start: i64,
+    pub end: i64,
+    pub count: usize,
+}
+
+/** Distribution of the totals of calories, when there is at least one elf. */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub median: f64,
+    pub percentiles: Vec<Percentile>,
+    pub histogram: Vec<Bucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statistics {
+    pub elves: Vec<ElfStatistics>,
+    pub summary: Option<Summary>,
+    /** The elves without any item, which the parser never gives but other callers can */
+    pub empty: Vec<usize>,
+    /** Groups of elves carrying the same items, in any order */
+    pub duplicates: Vec<Vec<usize>>,
+}
+
+/** Nearest-rank percentile of sorted totals. */
+pub fn percentile(sorted_totals: &[i64], percentile: u8) -> Option<i64> {
+    let rank = (percentile as usize * sorted_totals.len()).div_ceil(100);
+    return sorted_totals.get(rank.max(1) - 1).copied();
+}
+
+/**
+ * At most `buckets` buckets of the same width, from the smallest total to the biggest,
+ * the last one being cut at the biggest total. There are fewer buckets when the totals
+ * don't spread enough to give every bucket a different total.
+ */
+pub fn histogram(sorted_totals: &[i64], buckets: usize) -> Vec<Bucket> {
+    let (Some(&min), Some(&max)) = (sorted_totals.first(), sorted_totals.last()) else {
+        return Vec::new();
+    };
+    // In 128 bits, as there are up to 2^63 totals from 0 to i64::MAX
+    let range = (max as i128 - min as i128 + 1) as u128;
+    let width = range.div_ceil(buckets.max(1) as u128);
+    let mut histogram = (0..range.div_ceil(width))
+        .map(|bucket| {
+            let start = min as i128 + (bucket * width) as i128;
+            Bucket {
+                start: start as i64,
+                end: (start + width as i128 - 1).min(max as i128) as i64,
+                count: 0,
+            }
+        })
+        .collect::<Vec<Bucket>>();
+
+    let mut bucket = 0;
+    for &total in sorted_totals {
+        while total > histogram[bucket].end {
+            bucket += 1;
+        }
+        histogram[bucket].count += 1;
+    }
+    return histogram;
+}
+
+pub fn statistics(data: &[Vec<Calories>], buckets: usize) -> Statistics {
+    let elves = data
+        .iter()
+        .enumerate()
+        .map(|(elf, items)| ElfStatistics {
+            elf,
+            total: total(items).get(),
+            items: items.len(),
+        })
+        .collect::<Vec<ElfStatistics>>();
+
+    let mut sorted_totals = elves.iter().map(|elf| elf.total).collect::<Vec<i64>>();
+    sorted_totals.sort();
+    let summary = match (sorted_totals.first(), sorted_totals.last()) {
+        (Some(&min), Some(&max)) => {
+            let count = sorted_totals.len();
+            // In floating point, the sum of two totals can overflow
+            let middle = sorted_totals[(count - 1) / 2] as f64 + sorted_totals[count / 2] as f64;
+            Some(Summary {
+                min,
+                max,
+                mean: sorted_totals.iter().map(|&total| total as f64).sum::<f64>() / count as f64,
+                median: middle / 2.0,
+                percentiles: PERCENTILES
+                    .iter()
+                    .map(|&p| Percentile {
+                        percentile: p,
+                        total: percentile(&sorted_totals, p).unwrap(),
+                    })
+                    .collect(),
+                histogram: histogram(&sorted_totals, buckets),
+            })
+        }
+        _ => None,
+    };
+
+    // The same items in another order are the same inventory
+    let mut inventories = BTreeMap::<Vec<Calories>, Vec<usize>>::new();
+    for (elf, items) in data.iter().enumerate() {
+        let mut items = items.clone();
+        items.sort();
+        inventories.entry(items).or_default().push(elf);
+    }
+    let mut duplicates = inventories
+        .into_values()
+        .filter(|elves| elves.len() > 1)
+        .collect::<Vec<Vec<usize>>>();
+    duplicates.sort();
 
-    let biggest_sum = sums.max().expect("The dataset doesn't contain any numbers");
+    return Statistics {
+        empty: elves
+            .iter()
+            .filter(|elf| elf.items == 0)
+            .map(|elf| elf.elf)
+            .collect(),
+        elves,
+        summary,
+        duplicates,
+    };
+}
 
-    return biggest_sum;
+pub fn day_1_part_1(data: &[Vec<Calories>]) -> i64 {
+    return top_k_calories(data, 1).sum.get();
 }
 
-pub fn day_1_part_2(data: &str) -> i64 {
-    let data = parse_input_data(data);
+pub fn day_1_part_2(data: &[Vec<Calories>]) -> i64 {
+    return top_k_calories(data, 3).sum.get();
+}
 
-    let mut max_a = 0;
-    let mut max_b = 0;
-    let mut max_c = 0;
+/** Both parts without keeping the elves in memory, the empty lines are handled like the parser does. */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(i64, i64), Error> {
+    let mut top_one = TopK::new(1);
+    let mut top_three = TopK::new(3);
+    let mut all_elves = Calories::ZERO;
+    // None until the first item of the elf
+    let mut elf = None;
+    let mut elf_index = 0;
 
-    for elf in data {
-        let calories = elf.iter().sum();
-        if calories > max_a {
-            max_c = max_b;
-            max_b = max_a;
-            max_a = calories;
-        } else if calories > max_b {
-            max_c = max_b;
-            max_b = calories;
-        } else if calories > max_c {
-            max_c = calories;
+    let mut end_of_elf = |elf: Option<Calories>| {
+        if let Some(elf) = elf {
+            top_one.push(elf_index, elf);
+            top_three.push(elf_index, elf);
+            elf_index += 1;
         }
+    };
+
+    for_each_line(DAY, reader, |index, line| {
+        match parse_line(index, line)? {
+            Some(calories) => {
+                // The elf can't overflow if all the elves don't
+                all_elves = add_to_total(all_elves, calories, index, line)?;
+                elf = Some(elf.unwrap_or(Calories::ZERO).checked_add(calories).unwrap());
+            }
+            None => end_of_elf(elf.take()),
+        }
+        return Ok(());
+    })?;
+    end_of_elf(elf);
+
+    return Ok((top_one.top().sum.get(), top_three.top().sum.get()));
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<Vec<Calories>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
     }
 
-    return max_a + max_b + max_c;
+    fn part_1(data: &Self::Parsed) -> Answer {
+        return day_1_part_1(data).into();
+    }
+
+    fn part_2(data: &Self::Parsed) -> Answer {
+        return day_1_part_2(data).into();
+    }
+}
+
+impl StreamingSolution for Day01 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day01>();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "1000
-2000
-3000
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_01_example.txt");
 
-4000
+    fn calories(elves: &[&[i64]]) -> Vec<Vec<Calories>> {
+        return elves
+            .iter()
+            .map(|elf| elf.iter().map(|c| Calories::new(*c).unwrap()).collect())
+            .collect();
+    }
+
+    #[test]
+    fn test_day_1_part_1() {
+        assert_eq!(day_1_part_1(&parse_input_data(EXAMPLE).unwrap()), 24000);
+    }
 
-5000
-6000
+    #[test]
+    fn test_day_1_part_2() {
+        assert_eq!(day_1_part_2(&parse_input_data(EXAMPLE).unwrap()), 45000);
+    }
 
-7000
-8000
-9000
+    #[test]
+    fn test_top_k_calories() {
+        let elves = parse_input_data(EXAMPLE).unwrap();
+        assert_eq!(
+            top_k_calories(&elves, 3),
+            TopCalories {
+                indices: vec![3, 2, 4],
+                sum: Calories(45000)
+            }
+        );
+        assert_eq!(top_k_calories(&elves, 0).sum, Calories::ZERO);
+        assert_eq!(top_k_calories(&elves, 10).indices, vec![3, 2, 4, 0, 1]);
 
-10000";
+        // The first elves win the ties
+        let elves = calories(&[&[1], &[5], &[2, 3], &[5]]);
+        assert_eq!(top_k_calories(&elves, 2).indices, vec![1, 2]);
+    }
 
     #[test]
-    fn test_day_1_part_1() {
-        assert_eq!(day_1_part_1(EXAMPLE), 24000);
+    fn test_statistics() {
+        let statistics = statistics(&parse_input_data(EXAMPLE).unwrap(), 4);
+        assert_eq!(
+            statistics.elves[2],
+            ElfStatistics {
+                elf: 2,
+                total: 11000,
+                items: 2
+            }
+        );
+        let summary = statistics.summary.unwrap();
+        assert_eq!((summary.min, summary.max), (4000, 24000));
+        assert_eq!(summary.mean, 11000.0);
+        assert_eq!(summary.median, 10000.0);
+        assert_eq!(summary.percentiles[0].total, 4000);
+        assert_eq!(summary.percentiles[1].total, 6000);
+        assert_eq!(summary.percentiles[4].total, 24000);
+        assert_eq!(
+            summary.histogram,
+            vec![
+                Bucket {
+                    start: 4000,
+                    end: 9000,
+                    count: 2
+                },
+                Bucket {
+                    start: 9001,
+                    end: 14001,
+                    count: 2
+                },
+                Bucket {
+                    start: 14002,
+                    end: 19002,
+                    count: 0
+                },
+                Bucket {
+                    start: 19003,
+                    end: 24000,
+                    count: 1
+                },
+            ]
+        );
+        assert!(statistics.empty.is_empty());
+        assert!(statistics.duplicates.is_empty());
+
+        let statistics = super::statistics(&calories(&[&[1, 2], &[], &[2, 1], &[3], &[]]), 10);
+        assert_eq!(statistics.empty, vec![1, 4]);
+        assert_eq!(statistics.duplicates, vec![vec![0, 2], vec![1, 4]]);
+        assert_eq!(statistics.summary.unwrap().median, 3.0);
+        assert_eq!(super::statistics(&[], 10).summary, None);
     }
 
     #[test]
-    fn test_day_1_part_2() {
-        assert_eq!(day_1_part_2(EXAMPLE), 45000);
+    fn test_statistics_of_big_totals() {
+        let big = 4611686018427387904;
+        let summary = super::statistics(&calories(&[&[big]]), 10).summary.unwrap();
+        assert_eq!(summary.median, big as f64);
+        assert_eq!(summary.mean, big as f64);
+        assert_eq!(summary.histogram, vec![Bucket { start: big, end: big, count: 1 }]);
+
+        let summary = super::statistics(&calories(&[&[big, big - 1], &[0]]), 1).summary.unwrap();
+        assert_eq!(summary.median, i64::MAX as f64 / 2.0);
+        assert_eq!(
+            summary.histogram,
+            vec![Bucket { start: 0, end: i64::MAX, count: 2 }]
+        );
+
+        let histogram = histogram(&[0, 10, i64::MAX - 1, i64::MAX], 3);
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram.last().unwrap().end, i64::MAX);
+        assert_eq!(histogram.iter().map(|b| b.count).collect::<Vec<_>>(), vec![2, 0, 2]);
+    }
+
+    #[test]
+    fn test_histogram() {
+        // As many buckets as asked, the last one cut at the biggest total
+        let histogram = histogram(&[1, 2, 5, 9, 10], 3);
+        let buckets = histogram.iter().map(|b| (b.start, b.end, b.count)).collect::<Vec<_>>();
+        assert_eq!(buckets, vec![(1, 4, 2), (5, 8, 1), (9, 10, 2)]);
+        // Fewer totals than buckets
+        assert_eq!(super::histogram(&[3, 4], 10).len(), 2);
+        assert!(super::histogram(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("1000\n\n2000\nlots\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(1, 4, 1, "lots", "expected a number of calories")
+        );
+
+        let error = parse_input_data("1000\n-2000\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(1, 2, 1, "-2000", "expected a positive number of calories")
+        );
+
+        let error = parse_input_data("1000\n\n99999999999999999999").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(
+            error.message,
+            "expected a number of calories that fits in 64 bits"
+        );
+
+        // Each elf fits, but not all of them
+        let input = format!("{}\n\n{}\n\n1", i64::MAX - 1, 1);
+        let error = parse_input_data(&input).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(
+                1,
+                5,
+                1,
+                "1",
+                "expected fewer calories, the total of all the elves overflows"
+            )
+        );
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(ParseError { line: 5, .. })));
+    }
+
+    #[test]
+    fn test_empty_lines() {
+        let expected = calories(&[&[1000, 2000], &[3000], &[4000]]);
+        for input in [
+            "1000\n2000\n\n3000\n\n4000",
+            "\n\n1000\n2000\n\n\n\n3000\n\n4000\n\n\n",
+        ] {
+            assert_eq!(parse_input_data(input).unwrap(), expected);
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), (4000, 10000));
+        }
+        assert!(parse_input_data("\n\n").unwrap().is_empty());
+    }
+
+    fn elves() -> impl Strategy<Value = Vec<Vec<Calories>>> {
+        let calories = (0..100_000i64).prop_map(Calories);
+        return prop::collection::vec(prop::collection::vec(calories, 1..10), 1..50);
+    }
+
+    fn to_input(elves: &[Vec<Calories>]) -> String {
+        return elves
+            .iter()
+            .map(|elf| {
+                elf.iter()
+                    .map(|calories| calories.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n");
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        for input in [
+            EXAMPLE.to_string(),
+            crate::generate::generate(1, 1000, 0).unwrap(),
+        ] {
+            let elves = parse_input_data(&input).unwrap();
+            let expected = (day_1_part_1(&elves), day_1_part_2(&elves));
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), expected);
+        }
+
+        let input = "1000\n\n2000\nlots\n";
+        let expected = parse_input_data(input).unwrap_err();
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(error) if error == expected));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(elves in elves()) {
+            prop_assert_eq!(parse_input_data(&to_input(&elves)).unwrap(), elves);
+        }
+
+        #[test]
+        fn prop_top_three_contains_the_top_one(elves in elves()) {
+            let part_1 = day_1_part_1(&elves);
+            let part_2 = day_1_part_2(&elves);
+            prop_assert!(part_2 >= part_1);
+            prop_assert!(part_2 <= 3 * part_1);
+        }
+
+        #[test]
+        fn prop_top_k_is_the_k_biggest_totals(elves in elves(), k in 0..60usize) {
+            let top = top_k_calories(&elves, k);
+            let mut totals = elves.iter().map(|elf| total(elf).get()).collect::<Vec<i64>>();
+            totals.sort_by(|a, b| b.cmp(a));
+            prop_assert_eq!(top.indices.len(), k.min(elves.len()));
+            prop_assert_eq!(top.sum.get(), totals.iter().take(k).sum::<i64>());
+            for pair in top.indices.windows(2) {
+                prop_assert!(total(&elves[pair[0]]) >= total(&elves[pair[1]]));
+            }
+        }
+
+        #[test]
+        fn prop_generated_inputs(seed: u64, size in 1..200usize) {
+            let input = crate::generate::generate(1, size, seed).unwrap();
+            let elves = parse_input_data(&input).unwrap();
+            prop_assert_eq!(elves.len(), size);
+            prop_assert!(day_1_part_2(&elves) >= day_1_part_1(&elves));
+        }
+
+        #[test]
+        fn prop_histogram_covers_the_totals(
+            mut totals in prop::collection::vec(0..=i64::MAX, 1..50),
+            buckets in 1..20usize,
+        ) {
+            totals.sort();
+            let histogram = histogram(&totals, buckets);
+            prop_assert!(!histogram.is_empty() && histogram.len() <= buckets);
+            prop_assert_eq!(histogram[0].start, totals[0]);
+            prop_assert_eq!(histogram.last().unwrap().end, *totals.last().unwrap());
+            // Contiguous buckets, counting every total once
+            for pair in histogram.windows(2) {
+                prop_assert_eq!(pair[0].end + 1, pair[1].start);
+            }
+            for bucket in &histogram {
+                let count = totals.iter().filter(|t| (bucket.start..=bucket.end).contains(t));
+                prop_assert_eq!(bucket.count, count.count());
+            }
+        }
     }
 }
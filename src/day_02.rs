@@ -1,17 +1,45 @@
-use std::collections::VecDeque;
+use crate::error::{Error, ParseError};
+use crate::input::for_each_line;
+pub use crate::rps::GameResult;
+use crate::registry::Day;
+use crate::rps::{Move, Rules};
+use crate::solution::{Answer, Solution, StreamingSolution};
+use std::io::BufRead;
+use std::sync::OnceLock;
 
-#[derive(Debug, Clone)]
+const DAY: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Rock,
     Paper,
     Scissors,
 }
 
-#[derive(Debug)]
-pub enum GameResult {
-    Win,
-    Lose,
-    Draw,
+/** In the order of the moves of the classic rules. */
+const ACTIONS: [Action; 3] = [Action::Rock, Action::Paper, Action::Scissors];
+
+/** The second column of the strategy guide, its meaning changes between parts. */
+#[derive(Debug, PartialEq, Eq)]
+pub enum Strategy {
+    X,
+    Y,
+    Z,
+}
+
+/** How a part reads the strategy guide. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /** The strategy is the action to play, for part 1 */
+    Action,
+    /** The strategy is the result the game must have, for part 2 */
+    Result,
+}
+
+/** Rock paper scissors with the scores of the puzzle. */
+fn rules() -> &'static Rules {
+    static RULES: OnceLock<Rules> = OnceLock::new();
+    return RULES.get_or_init(Rules::classic);
 }
 
 impl std::str::FromStr for Action {
@@ -23,161 +51,258 @@ impl std::str::FromStr for Action {
             "A" => Ok(Action::Rock),
             "B" => Ok(Action::Paper),
             "C" => Ok(Action::Scissors),
-
-            // For Part 1
-            "X" => Ok(Action::Rock),
-            "Y" => Ok(Action::Paper),
-            "Z" => Ok(Action::Scissors),
             _ => Err(()),
         }
     }
 }
 
-impl std::str::FromStr for GameResult {
+impl std::str::FromStr for Strategy {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "X" => Ok(GameResult::Lose),
-            "Y" => Ok(GameResult::Draw),
-            "Z" => Ok(GameResult::Win),
+            "X" => Ok(Strategy::X),
+            "Y" => Ok(Strategy::Y),
+            "Z" => Ok(Strategy::Z),
             _ => Err(()),
         }
     }
 }
 
-impl Action {
-    pub fn against(&self, opponent_action: &Action) -> GameResult {
-        match (self, opponent_action) {
-            (Action::Rock, Action::Rock) => GameResult::Draw,
-            (Action::Rock, Action::Paper) => GameResult::Lose,
-            (Action::Rock, Action::Scissors) => GameResult::Win,
-            (Action::Paper, Action::Rock) => GameResult::Win,
-            (Action::Paper, Action::Paper) => GameResult::Draw,
-            (Action::Paper, Action::Scissors) => GameResult::Lose,
-            (Action::Scissors, Action::Rock) => GameResult::Lose,
-            (Action::Scissors, Action::Paper) => GameResult::Win,
-            (Action::Scissors, Action::Scissors) => GameResult::Draw,
+impl Strategy {
+    /** For Part 1 */
+    pub fn action(&self) -> Action {
+        match self {
+            Strategy::X => Action::Rock,
+            Strategy::Y => Action::Paper,
+            Strategy::Z => Action::Scissors,
         }
     }
 
-    pub fn points(&self) -> i64 {
+    /** For Part 2 */
+    pub fn game_result(&self) -> GameResult {
         match self {
-            Action::Rock => 1,
-            Action::Paper => 2,
-            Action::Scissors => 3,
+            Strategy::X => GameResult::Lose,
+            Strategy::Y => GameResult::Draw,
+            Strategy::Z => GameResult::Win,
         }
     }
 }
 
-impl GameResult {
+impl Action {
+    /** The move of the classic rules. */
+    pub fn to_move(self) -> Move {
+        return self as Move;
+    }
+
+    pub fn from_move(played: Move) -> Action {
+        return ACTIONS[played];
+    }
+
+    pub fn against(&self, opponent_action: &Action) -> GameResult {
+        return rules().result(self.to_move(), opponent_action.to_move());
+    }
+
     pub fn points(&self) -> i64 {
-        match self {
-            GameResult::Win => 6,
-            GameResult::Draw => 3,
-            GameResult::Lose => 0,
-        }
+        return rules().move_points(self.to_move());
     }
 
-    pub fn compute_move(&self, opponent_action: &Action) -> Action {
-        match self {
-            GameResult::Win => match opponent_action {
-                Action::Rock => Action::Paper,
-                Action::Paper => Action::Scissors,
-                Action::Scissors => Action::Rock,
-            },
-            GameResult::Draw => opponent_action.clone(),
-            GameResult::Lose => match opponent_action {
-                Action::Rock => Action::Scissors,
-                Action::Paper => Action::Rock,
-                Action::Scissors => Action::Paper,
-            },
-        }
+    /** The action giving this result against the opponent action. */
+    pub fn for_result(result: GameResult, opponent_action: &Action) -> Action {
+        let played = rules()
+            .move_for(result, opponent_action.to_move())
+            .expect("Every result is possible against every move");
+        return Action::from_move(played);
     }
 }
 
-pub fn parse_input_data_part_1(input: &str) -> Vec<(Action, Action)> {
-    return input
-        // Split by lines
-        .lines()
-        .map(|game| {
-            let mut actions = game
-                .split_whitespace()
-                .map(|action| action.parse::<Action>().expect("Failed to parse action"))
-                .collect::<VecDeque<Action>>();
-            return (
-                actions.pop_front().expect("Failed to get action"),
-                actions.pop_front().expect("Failed to get action"),
-            );
-        })
-        .collect();
+fn parse_game(index: usize, game: &str) -> Result<(Action, Strategy), ParseError> {
+    let error =
+        |token: &str, message: &str| ParseError::at_token(DAY, index + 1, game, token, message);
+
+    let mut tokens = game.split_whitespace();
+    let action_str = tokens
+        .next()
+        .ok_or_else(|| error(game, "expected the opponent action"))?;
+    let action = action_str
+        .parse::<Action>()
+        .map_err(|_| error(action_str, "expected A, B or C"))?;
+    let strategy_str = tokens
+        .next()
+        .ok_or_else(|| error(&game[game.len()..], "expected a strategy"))?;
+    let strategy = strategy_str
+        .parse::<Strategy>()
+        .map_err(|_| error(strategy_str, "expected X, Y or Z"))?;
+    if let Some(extra) = tokens.next() {
+        return Err(error(extra, "expected the end of the line"));
+    }
+    return Ok((action, strategy));
 }
 
-pub fn parse_input_data_part_2(input: &str) -> Vec<(Action, GameResult)> {
+pub fn parse_input_data(input: &str) -> Result<Vec<(Action, Strategy)>, ParseError> {
     return input
         .lines()
-        .map(|game| {
-            let mut tuple = game.split_whitespace().collect::<VecDeque<&str>>();
-            let action = tuple
-                .pop_front()
-                .expect("Failed to get action")
-                .parse::<Action>()
-                .expect("Failed to parse action");
-            let game_result = tuple
-                .pop_front()
-                .expect("Failed to get game result")
-                .parse::<GameResult>()
-                .expect("Failed to parse game result");
-            return (action, game_result);
-        })
+        .enumerate()
+        .map(|(index, game)| parse_game(index, game))
         .collect();
 }
 
-pub fn day_2_part_1(data: &str) -> i64 {
-    let played_actions = parse_input_data_part_1(data);
-
-    let game_results = played_actions.iter().map(|(a, b)| b.against(a));
+fn score(reading: Reading, opponent_action: &Action, strategy: &Strategy) -> i64 {
+    let action = match reading {
+        Reading::Action => strategy.action(),
+        Reading::Result => Action::for_result(strategy.game_result(), opponent_action),
+    };
+    return rules().score(action.to_move(), opponent_action.to_move());
+}
 
-    let score = played_actions
+pub fn total_score(strategy_guide: &[(Action, Strategy)], reading: Reading) -> i64 {
+    return strategy_guide
         .iter()
-        .map(|(_, action)| action.points())
-        .sum::<i64>()
-        + game_results.map(|result| result.points()).sum::<i64>();
+        .map(|(opponent_action, strategy)| score(reading, opponent_action, strategy))
+        .sum();
+}
 
-    return score;
+pub fn day_2_part_1(strategy_guide: &[(Action, Strategy)]) -> i64 {
+    return total_score(strategy_guide, Reading::Action);
 }
 
-pub fn day_2_part_2(data: &str) -> i64 {
-    let game_results = parse_input_data_part_2(data);
+pub fn day_2_part_2(strategy_guide: &[(Action, Strategy)]) -> i64 {
+    return total_score(strategy_guide, Reading::Result);
+}
 
-    let played_actions = game_results
-        .iter()
-        .map(|(action, game_result)| game_result.compute_move(action));
+/** Both parts without keeping the strategy guide in memory. */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(i64, i64), Error> {
+    let mut part_1 = 0;
+    let mut part_2 = 0;
+    for_each_line(DAY, reader, |index, game| {
+        let (opponent_action, strategy) = parse_game(index, game)?;
+        part_1 += score(Reading::Action, &opponent_action, &strategy);
+        part_2 += score(Reading::Result, &opponent_action, &strategy);
+        return Ok(());
+    })?;
+    return Ok((part_1, part_2));
+}
 
-    let score = played_actions.map(|action| action.points()).sum::<i64>()
-        + game_results
-            .iter()
-            .map(|(_, game_result)| game_result.points())
-            .sum::<i64>();
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<(Action, Strategy)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
 
-    return score;
+    fn part_1(strategy_guide: &Self::Parsed) -> Answer {
+        return day_2_part_1(strategy_guide).into();
+    }
+
+    fn part_2(strategy_guide: &Self::Parsed) -> Answer {
+        return day_2_part_2(strategy_guide).into();
+    }
+}
+
+impl StreamingSolution for Day02 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day02>();
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Strategy;
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "A Y
-    B X
-    C Z";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_02_example.txt");
 
     #[test]
     fn test_day_2_part_1() {
-        assert_eq!(day_2_part_1(EXAMPLE), 15);
+        assert_eq!(day_2_part_1(&parse_input_data(EXAMPLE).unwrap()), 15);
     }
 
     #[test]
     fn test_day_2_part_2() {
-        assert_eq!(day_2_part_2(EXAMPLE), 12);
+        assert_eq!(day_2_part_2(&parse_input_data(EXAMPLE).unwrap()), 12);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("A Y\nB W").unwrap_err();
+        assert_eq!(error, ParseError::new(2, 2, 3, "W", "expected X, Y or Z"));
+        let error = parse_input_data("A Y\nB").unwrap_err();
+        assert_eq!(error, ParseError::new(2, 2, 2, "", "expected a strategy"));
+    }
+
+    fn strategy_guide() -> impl proptest::strategy::Strategy<Value = Vec<(usize, usize)>> {
+        return prop::collection::vec((0..3usize, 0..3usize), 0..200);
+    }
+
+    fn to_input(games: &[(usize, usize)]) -> String {
+        return games
+            .iter()
+            .map(|(action, strategy)| {
+                format!(
+                    "{} {}\n",
+                    ["A", "B", "C"][*action],
+                    ["X", "Y", "Z"][*strategy]
+                )
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        for input in [
+            EXAMPLE.to_string(),
+            crate::generate::generate(2, 1000, 0).unwrap(),
+        ] {
+            let guide = parse_input_data(&input).unwrap();
+            let expected = (day_2_part_1(&guide), day_2_part_2(&guide));
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), expected);
+        }
+
+        let input = "A Y\nB W";
+        let expected = parse_input_data(input).unwrap_err();
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(error) if error == expected));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(games in strategy_guide()) {
+            let parsed = parse_input_data(&to_input(&games)).unwrap();
+            let actions = ACTIONS;
+            let strategies = [Strategy::X, Strategy::Y, Strategy::Z];
+            prop_assert_eq!(parsed.len(), games.len());
+            for ((action, strategy), (action_index, strategy_index)) in parsed.iter().zip(games) {
+                prop_assert_eq!(action, &actions[action_index]);
+                prop_assert_eq!(strategy, &strategies[strategy_index]);
+            }
+        }
+
+        #[test]
+        fn prop_scores_are_bounded(games in strategy_guide()) {
+            // Every game is worth between 1 and 9 points
+            let parsed = parse_input_data(&to_input(&games)).unwrap();
+            let nb_games = games.len() as i64;
+            for score in [day_2_part_1(&parsed), day_2_part_2(&parsed)] {
+                prop_assert!(score >= nb_games);
+                prop_assert!(score <= 9 * nb_games);
+            }
+        }
+
+        #[test]
+        fn prop_played_moves_give_the_planned_results(opponent in 0..3usize, strategy in 0..3usize) {
+            let opponent = ACTIONS[opponent];
+            let planned = [Strategy::X, Strategy::Y, Strategy::Z][strategy].game_result();
+            let played = Action::for_result(planned, &opponent);
+            prop_assert_eq!(played.against(&opponent), planned);
+        }
     }
 }
@@ -1,31 +1,97 @@
-pub fn parse_input_data(input: &str) -> Vec<Vec<u8>> {
-    return input
-        .lines()
-        .map(|backpack_line| {
-            // Split line by characters
-            return backpack_line
-                .chars()
-                .into_iter()
-                .map(|character| {
-                    // Convert each character to the "priority"
-                    // a-z => 1-26
-                    // A-Z => 27-52
-                    return match character {
-                        'a'..='z' => character as u8 - 'a' as u8 + 1,
-                        'A'..='Z' => character as u8 - 'A' as u8 + 27,
-                        _ => panic!("Invalid character"),
-                    };
-                })
-                .collect();
+use crate::error::{Error, ParseError};
+use crate::input::for_each_line;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
+use std::io::BufRead;
+
+const DAY: u8 = 3;
+
+fn parse_backpack(index: usize, backpack_line: &str) -> Result<Vec<u8>, ParseError> {
+    // Split line by characters
+    return backpack_line
+        .chars()
+        .enumerate()
+        .map(|(column, character)| {
+            // Convert each character to the "priority"
+            // a-z => 1-26
+            // A-Z => 27-52
+            return match character {
+                'a'..='z' => Ok(character as u8 - b'a' + 1),
+                'A'..='Z' => Ok(character as u8 - b'A' + 27),
+                _ => Err(ParseError::new(
+                    DAY,
+                    index + 1,
+                    column + 1,
+                    &character.to_string(),
+                    "expected an item letter",
+                )),
+            };
         })
         .collect();
 }
 
+/** The item in both halves of the backpack, which must exist for part 1. */
+fn check_backpack(index: usize, backpack_line: &str, backpack: &[u8]) -> Result<u8, ParseError> {
+    return find_number_in_both_halfes_of_the_list(backpack.to_vec()).map_err(|_| {
+        ParseError::new(
+            DAY,
+            index + 1,
+            1,
+            backpack_line,
+            "expected an item in both halves of the backpack",
+        )
+    });
+}
+
+/** The badge of a group, which must exist for part 2, reported on its last backpack. */
+fn check_group(index: usize, backpack_line: &str, group: &[Vec<u8>]) -> Result<u8, ParseError> {
+    return find_common_number_in_three_lists(group[0].clone(), group[1].clone(), group[2].clone())
+        .map_err(|_| {
+            ParseError::new(
+                DAY,
+                index + 1,
+                1,
+                backpack_line,
+                "expected an item in the three backpacks of the group",
+            )
+        });
+}
+
+fn incomplete_group(nb_lines: usize) -> ParseError {
+    return ParseError::new(
+        DAY,
+        nb_lines + 1,
+        1,
+        "",
+        "expected another backpack, the groups have three backpacks",
+    );
+}
+
+/**
+ * The backpacks without an item in both halves, the groups without a common item,
+ * and an incomplete last group are errors, so both parts always have an answer.
+ */
+pub fn parse_input_data(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut backpacks = Vec::new();
+    for (index, backpack_line) in input.lines().enumerate() {
+        let backpack = parse_backpack(index, backpack_line)?;
+        check_backpack(index, backpack_line, &backpack)?;
+        backpacks.push(backpack);
+        if backpacks.len() % 3 == 0 {
+            check_group(index, backpack_line, &backpacks[backpacks.len() - 3..])?;
+        }
+    }
+    if backpacks.len() % 3 != 0 {
+        return Err(incomplete_group(backpacks.len()));
+    }
+    return Ok(backpacks);
+}
+
 pub fn find_number_in_both_halfes_of_the_list(list: Vec<u8>) -> Result<u8, String> {
     // We know the numbers are betwen 1 and 52
     // We use a set to keep track of which numbers we've seen
     // But since it's so few numbers, a boolean array is faster
-    let mut hit_table = vec![false; 52];
+    let mut hit_table = [false; 52];
 
     // Fill the table with the first half of the list
     for number in list.iter().take(list.len() / 2) {
@@ -73,22 +139,20 @@ pub fn find_common_number_in_three_lists(
     return Result::Err("No number found".to_string());
 }
 
-pub fn day_3_part_1(data: &str) -> i64 {
-    let backpacks = parse_input_data(data);
-
+pub fn day_3_part_1(backpacks: &[Vec<u8>]) -> i64 {
     let sum: i64 = backpacks
         .iter()
         .map(|backpack| {
-            return find_number_in_both_halfes_of_the_list(backpack.clone()).unwrap() as i64;
+            return find_number_in_both_halfes_of_the_list(backpack.clone())
+                .expect("The backpacks have been checked when parsing")
+                as i64;
         })
         .sum();
 
     return sum;
 }
 
-pub fn day_3_part_2(data: &str) -> i64 {
-    let backpacks = parse_input_data(data);
-
+pub fn day_3_part_2(backpacks: &[Vec<u8>]) -> i64 {
     // Iterate the vector in chunks of 3
     let sum: i64 = backpacks
         .chunks(3)
@@ -98,31 +162,181 @@ pub fn day_3_part_2(data: &str) -> i64 {
                 backpacks[1].clone(),
                 backpacks[2].clone(),
             )
-            .unwrap() as i64;
+            .expect("The groups have been checked when parsing") as i64;
         })
         .sum();
 
     return sum;
 }
 
+/**
+ * Both parts while only keeping the current group of three backpacks in memory,
+ * with the same checks and errors as `parse_input_data`.
+ */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(i64, i64), Error> {
+    let mut part_1 = 0;
+    let mut part_2 = 0;
+    let mut group = Vec::with_capacity(3);
+    let mut nb_lines = 0;
+    for_each_line(DAY, reader, |index, backpack_line| {
+        nb_lines = index + 1;
+        let backpack = parse_backpack(index, backpack_line)?;
+        part_1 += check_backpack(index, backpack_line, &backpack)? as i64;
+
+        group.push(backpack);
+        if group.len() == 3 {
+            part_2 += check_group(index, backpack_line, &group)? as i64;
+            group.clear();
+        }
+        return Ok(());
+    })?;
+    if !group.is_empty() {
+        return Err(incomplete_group(nb_lines).into());
+    }
+    return Ok((part_1, part_2));
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<Vec<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(backpacks: &Self::Parsed) -> Answer {
+        return day_3_part_1(backpacks).into();
+    }
+
+    fn part_2(backpacks: &Self::Parsed) -> Answer {
+        return day_3_part_2(backpacks).into();
+    }
+}
+
+impl StreamingSolution for Day03 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day03>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_03_example.txt");
 
     #[test]
     fn test_day_3_part_1() {
-        assert_eq!(day_3_part_1(EXAMPLE), 157);
+        assert_eq!(day_3_part_1(&parse_input_data(EXAMPLE).unwrap()), 157);
     }
 
     #[test]
     fn test_day_3_part_2() {
-        assert_eq!(day_3_part_2(EXAMPLE), 70);
+        assert_eq!(day_3_part_2(&parse_input_data(EXAMPLE).unwrap()), 70);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNq-RjqzjGDLGL").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(3, 2, 7, "-", "expected an item letter")
+        );
+    }
+
+    /** Inputs which are made of items, but have no answer */
+    const UNSOLVABLE: [(&str, usize, &str); 3] = [
+        (
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n",
+            3,
+            "expected another backpack, the groups have three backpacks",
+        ),
+        ("abcd\n", 1, "expected an item in both halves of the backpack"),
+        ("aa\nbb\ncc\n", 3, "expected an item in the three backpacks of the group"),
+    ];
+
+    #[test]
+    fn test_unsolvable_inputs() {
+        for (input, line, message) in UNSOLVABLE {
+            let error = parse_input_data(input).unwrap_err();
+            assert_eq!((error.line, error.message.as_str()), (line, message));
+            // The first error is reported, even when a later line is malformed
+            let error = parse_input_data(&format!("{}a-\n", input)).unwrap_err();
+            assert_eq!(error.line, line);
+        }
+    }
+
+    fn priority_to_letter(priority: u8) -> char {
+        return match priority {
+            1..=26 => (b'a' + priority - 1) as char,
+            _ => (b'A' + priority - 27) as char,
+        };
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        for input in [
+            EXAMPLE.to_string(),
+            crate::generate::generate(3, 1000, 0).unwrap(),
+        ] {
+            let backpacks = parse_input_data(&input).unwrap();
+            let expected = (day_3_part_1(&backpacks), day_3_part_2(&backpacks));
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), expected);
+        }
+
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNq-RjqzjGDLGL";
+        let expected = parse_input_data(input).unwrap_err();
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(error) if error == expected));
+
+        // The same errors as when parsing the whole input
+        for (input, _, _) in UNSOLVABLE {
+            let expected = parse_input_data(input).unwrap_err();
+            let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+            assert!(matches!(error, Error::Parse(error) if error == expected));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(backpack in "[a-zA-Z]{0,40}", index in 0..30usize) {
+            let parsed = parse_backpack(index, &backpack).unwrap();
+            let letters = parsed.iter().map(|p| priority_to_letter(*p)).collect::<String>();
+            prop_assert_eq!(letters, backpack);
+        }
+
+        #[test]
+        fn prop_item_in_both_halves_is_found(
+            items in prop::collection::vec((1..=26u8, 27..=52u8), 1..20),
+            shared in 1..=52u8,
+            position in any::<prop::sample::Index>(),
+        ) {
+            // Lowercase items in the first half, uppercase ones in the second
+            let (mut first_half, mut second_half): (Vec<u8>, Vec<u8>) = items.into_iter().unzip();
+            let index = position.index(first_half.len());
+            first_half[index] = shared;
+            second_half[index] = shared;
+            let list = [first_half, second_half].concat();
+            prop_assert_eq!(find_number_in_both_halfes_of_the_list(list), Ok(shared));
+        }
+
+        #[test]
+        fn prop_generated_inputs(seed: u64, size in 1..100usize) {
+            let input = crate::generate::generate(3, size, seed).unwrap();
+            let backpacks = parse_input_data(&input).unwrap();
+            let nb_backpacks = backpacks.len() as i64;
+            let part_1 = day_3_part_1(&backpacks);
+            let part_2 = day_3_part_2(&backpacks);
+            prop_assert!(part_1 >= nb_backpacks && part_1 <= 52 * nb_backpacks);
+            prop_assert!(part_2 >= nb_backpacks / 3 && part_2 <= 52 * nb_backpacks / 3);
+        }
     }
 }
@@ -1,41 +1,79 @@
-#[derive(Debug)]
+use crate::error::{Error, ParseError};
+use crate::input::for_each_line;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
+use std::io::BufRead;
+
+/** Inclusive range of section IDs */
+#[derive(Debug, PartialEq, Eq)]
 pub struct Range {
-    start: i64,
-    end: i64,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
 }
 
-pub fn parse_input_data(input: &str) -> Vec<(Range, Range)> {
+const DAY: u8 = 4;
+
+fn parse_pair(index: usize, line: &str) -> Result<(Range, Range), ParseError> {
+    // format
+    // 2-4,6-8
+    // Range<start, end>,Range<start, end>
+    // use scanf to parse
+    let parsed = sscanf::sscanf!(line, "{i64}-{i64},{i64}-{i64}").map_err(|_| {
+        ParseError::new(DAY, index + 1, 1, line, "expected two ranges like 2-4,6-8")
+    })?;
+    let range1 = Range {
+        start: parsed.0,
+        end: parsed.1,
+    };
+    let range2 = Range {
+        start: parsed.2,
+        end: parsed.3,
+    };
+    let (text1, text2) = line.split_once(',').expect("The line has been scanned");
+    for (range, text) in [(&range1, text1), (&range2, text2)] {
+        if range.start > range.end {
+            return Err(ParseError::at_token(
+                DAY,
+                index + 1,
+                line,
+                text,
+                "expected a range starting before its end",
+            ));
+        }
+    }
+    return Ok((range1, range2));
+}
+
+pub fn parse_input_data(input: &str) -> Result<Vec<(Range, Range)>, ParseError> {
     return input
         .lines()
-        .map(|line| {
-            // format
-            // 2-4,6-8
-            // Range<start, end>,Range<start, end>
-            // use scanf to parse
-            let parsed =
-                sscanf::sscanf!(line, "{i64}-{i64},{i64}-{i64}").expect("Unable to parse line");
-            let range1 = Range {
-                start: parsed.0,
-                end: parsed.1,
-            };
-            let range2 = Range {
-                start: parsed.2,
-                end: parsed.3,
-            };
-            return (range1, range2);
-        })
+        .enumerate()
+        .map(|(index, line)| parse_pair(index, line))
         .collect();
 }
 
-pub fn day_4_part_1(data: &str) -> i64 {
-    let ranges = parse_input_data(data);
+/** Check if one range fits completely into the other */
+fn fully_contains(range1: &Range, range2: &Range) -> bool {
+    return (range1.start >= range2.start && range1.end <= range2.end)
+        || (range2.start >= range1.start && range2.end <= range1.end);
+}
+
+/** Check if one range overlaps with the other */
+fn overlaps(range1: &Range, range2: &Range) -> bool {
+    return (range1.start <= range2.start && range1.end >= range2.start)
+        || (range2.start <= range1.start && range2.end >= range1.start);
+}
 
+pub fn day_4_part_1(ranges: &[(Range, Range)]) -> i64 {
     let mut sum = 0;
     for (range1, range2) in ranges.iter() {
-        // Check if one range fits completely into the other
-        if (range1.start >= range2.start && range1.end <= range2.end)
-            || (range2.start >= range1.start && range2.end <= range1.end)
-        {
+        if fully_contains(range1, range2) {
             sum += 1;
         }
     }
@@ -43,15 +81,10 @@ pub fn day_4_part_1(data: &str) -> i64 {
     return sum;
 }
 
-pub fn day_4_part_2(data: &str) -> i64 {
-    let ranges = parse_input_data(data);
-
+pub fn day_4_part_2(ranges: &[(Range, Range)]) -> i64 {
     let mut sum = 0;
     for (range1, range2) in ranges.iter() {
-        // Check if one range overlaps with the other
-        if (range1.start <= range2.start && range1.end >= range2.start)
-            || (range2.start <= range1.start && range2.end >= range1.start)
-        {
+        if overlaps(range1, range2) {
             sum += 1;
         }
     }
@@ -59,24 +92,147 @@ pub fn day_4_part_2(data: &str) -> i64 {
     return sum;
 }
 
+/** Both parts without keeping the pairs in memory. */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(i64, i64), Error> {
+    let mut part_1 = 0;
+    let mut part_2 = 0;
+    for_each_line(DAY, reader, |index, line| {
+        let (range1, range2) = parse_pair(index, line)?;
+        part_1 += fully_contains(&range1, &range2) as i64;
+        part_2 += overlaps(&range1, &range2) as i64;
+        return Ok(());
+    })?;
+    return Ok((part_1, part_2));
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(ranges: &Self::Parsed) -> Answer {
+        return day_4_part_1(ranges).into();
+    }
+
+    fn part_2(ranges: &Self::Parsed) -> Answer {
+        return day_4_part_2(ranges).into();
+    }
+}
+
+impl StreamingSolution for Day04 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day04>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_04_example.txt");
 
     #[test]
     fn test_day_4_part_1() {
-        assert_eq!(day_4_part_1(EXAMPLE), 2);
+        assert_eq!(day_4_part_1(&parse_input_data(EXAMPLE).unwrap()), 2);
     }
 
     #[test]
     fn test_day_4_part_2() {
-        assert_eq!(day_4_part_2(EXAMPLE), 4);
+        assert_eq!(day_4_part_2(&parse_input_data(EXAMPLE).unwrap()), 4);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("2-4,6-8\n2-3;4-5").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(4, 2, 1, "2-3;4-5", "expected two ranges like 2-4,6-8")
+        );
+
+        let message = "expected a range starting before its end";
+        let error = parse_input_data("5-2,1-3").unwrap_err();
+        assert_eq!(error, ParseError::new(4, 1, 1, "5-2", message));
+        let error = parse_input_data("2-4,6-8\n1-3,12-10").unwrap_err();
+        assert_eq!(error, ParseError::new(4, 2, 5, "12-10", message));
+        // A single section is a range
+        assert!(parse_input_data("3-3,3-3").is_ok());
+    }
+
+    fn range() -> impl Strategy<Value = Range> {
+        return (1..100i64, 0..100i64).prop_map(|(start, length)| Range {
+            start,
+            end: start + length,
+        });
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        for input in [
+            EXAMPLE.to_string(),
+            crate::generate::generate(4, 1000, 0).unwrap(),
+        ] {
+            let ranges = parse_input_data(&input).unwrap();
+            let expected = (day_4_part_1(&ranges), day_4_part_2(&ranges));
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), expected);
+        }
+
+        let input = "2-4,6-8\n2-3;4-5";
+        let expected = parse_input_data(input).unwrap_err();
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(error) if error == expected));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(pairs in prop::collection::vec((range(), range()), 0..50)) {
+            let input = pairs
+                .iter()
+                .map(|(range1, range2)| format!("{},{}", range1, range2))
+                .collect::<Vec<String>>()
+                .join("\n");
+            prop_assert_eq!(parse_input_data(&input).unwrap(), pairs);
+        }
+
+        #[test]
+        fn prop_contained_pairs_overlap(pairs in prop::collection::vec((range(), range()), 0..50)) {
+            let part_1 = day_4_part_1(&pairs);
+            let part_2 = day_4_part_2(&pairs);
+            prop_assert!(part_1 <= part_2);
+            prop_assert!(part_2 <= pairs.len() as i64);
+        }
+
+        #[test]
+        fn prop_a_range_contains_itself(range in range()) {
+            let pair = (Range { ..range }, Range { start: range.start, end: range.end });
+            prop_assert_eq!(day_4_part_1(&[pair]), 1);
+        }
+
+        #[test]
+        fn prop_reversed_ranges_are_rejected(
+            (range1, range2) in (range(), range()),
+            length in 1..100i64,
+            first: bool,
+        ) {
+            let reversed = format!("{}-{}", range1.end + length, range1.start);
+            let (line, column) = match first {
+                true => (format!("{},{}", reversed, range2), 1),
+                false => (format!("{},{}", range2, reversed), range2.to_string().len() + 2),
+            };
+            let error = parse_input_data(&line).unwrap_err();
+            prop_assert_eq!((error.column, error.text.as_str()), (column, reversed.as_str()));
+            let streamed = solve_stream(&mut line.as_bytes()).unwrap_err();
+            prop_assert!(matches!(streamed, Error::Parse(streamed) if streamed == error));
+        }
     }
 }
@@ -1,33 +1,89 @@
+use crate::error::ParseError;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution};
+use std::borrow::Cow;
 use std::collections::VecDeque;
 
+pub type Stacks = Vec<VecDeque<char>>;
+
+/** Number of crates to move, from stack, to stack. */
+pub type Move = (usize, usize, usize);
+
+const DAY: u8 = 5;
+
 /** On Day 5, the fun part is the parsing. */
-pub fn parse_input_data(input: &str) -> (Vec<VecDeque<char>>, Vec<(usize, usize, usize)>) {
+pub fn parse_input_data(input: &str) -> Result<(Stacks, Vec<Move>), ParseError> {
     // Split the input string in two strings, where the empty line is
-    let mut split = input.split("\n\n");
-    let stacks_str = split.next().expect("Unable to get first part of input");
-    let instructions_str = split.next().expect("Unable to get second part of input");
+    let (stacks_str, instructions_str) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(
+            DAY,
+            input.lines().count() + 1,
+            1,
+            "",
+            "expected an empty line between the stacks and the moves",
+        )
+    })?;
 
     //println!("Stacks: {}", stacks_str);
     //println!("Instructions: {}", instructions_str);
 
-    let stacks = parse_stacks(stacks_str);
-    let instructions = parse_instructions(instructions_str);
+    let stacks = parse_stacks(stacks_str)?;
+    // The moves start after the stacks and the empty line
+    let first_line = stacks_str.lines().count() + 2;
+    let heights = stacks.iter().map(|stack| stack.len()).collect::<Vec<usize>>();
+    let instructions = parse_instructions(instructions_str, &heights, first_line)?;
 
-    return (stacks, instructions);
+    return Ok((stacks, instructions));
 }
 
-pub fn parse_stacks(input: &str) -> Vec<VecDeque<char>> {
+/**
+ * Pads the lines of the stacks with spaces, so they are as wide as all the stacks,
+ * as the trailing whitespaces are often removed by the editors.
+ */
+pub fn pad_stacks(input: &str) -> Cow<'_, str> {
+    let (stacks_str, instructions_str) = match input.split_once("\n\n") {
+        Some((stacks_str, instructions_str)) => (stacks_str, Some(instructions_str)),
+        None => (input.trim_end_matches('\n'), None),
+    };
+    let lengths = stacks_str.lines().map(|line| line.chars().count());
+    // The crates are 3 characters wide, and separated by a space
+    let width = (lengths.clone().max().unwrap_or_default() + 1).div_ceil(4) * 4 - 1;
+    if lengths.clone().all(|length| length == width) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut padded = String::with_capacity(input.len() + width);
+    for (line, length) in stacks_str.lines().zip(lengths) {
+        padded.push_str(line);
+        padded.push_str(&" ".repeat(width - length));
+        padded.push('\n');
+    }
+    if let Some(instructions_str) = instructions_str {
+        padded.push('\n');
+        padded.push_str(instructions_str);
+    }
+    return Cow::Owned(padded);
+}
+
+pub fn parse_stacks(input: &str) -> Result<Stacks, ParseError> {
     let lines = input.lines().collect::<Vec<&str>>();
 
-    let lines_length = lines.get(0).expect("Unable to get first line").len();
+    let lines_length = lines
+        .first()
+        .ok_or_else(|| ParseError::new(DAY, 1, 1, "", "expected a stack of crates"))?
+        .chars()
+        .count();
     // Check that all lines have the same length
-    for line in lines.iter().skip(1) {
-        if line.len() != lines_length {
-            panic!(
-                "Line {} has a different length than the first line. \
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        if line.chars().count() != lines_length {
+            return Err(ParseError::new(
+                DAY,
+                index + 1,
+                1,
+                line,
+                "expected the same length as the first line. \
                     Did your editor remove trailing whitespaces without telling you?",
-                line
-            );
+            ));
         }
     }
 
@@ -40,58 +96,109 @@ pub fn parse_stacks(input: &str) -> Vec<VecDeque<char>> {
     }
 
     // For every line except the last one
-    for line in lines.iter().take(lines.len() - 1) {
+    for (index, line) in lines.iter().enumerate().take(lines.len() - 1) {
         let chars = line.chars().collect::<Vec<char>>();
         // For every fourth character in the line, starting at 2
         for i in (1..lines_length).step_by(4) {
-            let chararacter = chars.get(i).expect("Unable to get character");
-            // If Character is not a space but between A and Z
-            if *chararacter != ' ' && *chararacter >= 'A' && *chararacter <= 'Z' {
-                // Add it to the stack
-                let stack_index = (i - 1) / 4;
-                stacks
-                    .get_mut(stack_index)
-                    .expect("Unable to get stack")
-                    .push_front(*chararacter);
+            let chararacter = chars[i];
+            match chararacter {
+                ' ' => {}
+                'A'..='Z' => {
+                    // Add it to the stack
+                    let stack_index = (i - 1) / 4;
+                    stacks[stack_index].push_front(chararacter);
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        DAY,
+                        index + 1,
+                        i + 1,
+                        &chararacter.to_string(),
+                        "expected a crate letter or a space",
+                    ))
+                }
             }
         }
     }
 
     //println!("Stacks: {:?}", stacks);
-    return stacks;
+    return Ok(stacks);
 }
 
-pub fn parse_instructions(input: &str) -> Vec<(usize, usize, usize)> {
-    return input
-        .lines()
-        .map(|line| {
-            //println!("Line: {}", line);
-            let (moves, from, to) = sscanf::sscanf!(line, "move {usize} from {usize} to {usize}")
-                .expect("Unable to parse line");
-            return (moves, from, to);
-        })
-        .collect();
+/**
+ * `heights` are the numbers of crates of the stacks before the moves, and `first_line`
+ * is the line number of the first move in the whole input, right after the stack numbers
+ * and the empty line. The moves are played on the heights, so a move can't take more crates
+ * than its stack holds, and every stack must keep a crate for the signature.
+ */
+pub fn parse_instructions(
+    input: &str,
+    heights: &[usize],
+    first_line: usize,
+) -> Result<Vec<Move>, ParseError> {
+    let nb_stack = heights.len();
+    let mut heights = heights.to_vec();
+    // The last move taking the last crate of each stack
+    let mut emptied_by = vec![None; nb_stack];
+    let mut instructions = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        //println!("Line: {}", line);
+        let line_number = first_line + index;
+        let error = |message: &str| ParseError::new(DAY, line_number, 1, line, message);
+        let (moves, from, to) = sscanf::sscanf!(line, "move {usize} from {usize} to {usize}")
+            .map_err(|_| error("expected a move like move 1 from 2 to 3"))?;
+        if !(1..=nb_stack).contains(&from) || !(1..=nb_stack).contains(&to) {
+            return Err(error("expected existing stack numbers"));
+        }
+        if moves > heights[from - 1] {
+            return Err(error("expected at most as many crates as the stack holds"));
+        }
+        heights[from - 1] -= moves;
+        heights[to - 1] += moves;
+        if heights[from - 1] == 0 {
+            emptied_by[from - 1] = Some((line_number, line));
+        }
+        instructions.push((moves, from, to));
+    }
+
+    if let Some(stack) = heights.iter().position(|&height| height == 0) {
+        let message = "expected a crate left on every stack after the moves";
+        return Err(match emptied_by[stack] {
+            Some((line_number, line)) => ParseError::new(DAY, line_number, 1, line, message),
+            // Empty from the start, reported on its number
+            None => ParseError::new(
+                DAY,
+                first_line - 2,
+                stack * 4 + 2,
+                &(stack + 1).to_string(),
+                message,
+            ),
+        });
+    }
+    return Ok(instructions);
 }
 
-pub fn get_stacks_signature(stacks: &Vec<VecDeque<char>>) -> String {
+pub fn get_stacks_signature(stacks: &[VecDeque<char>]) -> String {
     // Concatenate the first string of each stack
     return stacks
         .iter()
         .map(|stack| {
             stack
                 .back()
-                .expect("Unable to get first character")
+                .expect("Every stack keeps a crate, checked when parsing")
                 .to_string()
         })
         .collect::<Vec<String>>()
         .join("");
 }
 
-pub fn day_5_part_1(data: &str) -> String {
-    let (stacks, instructions) = parse_input_data(data);
-    let mut stacks = stacks;
+pub fn day_5_part_1(data: &(Stacks, Vec<Move>)) -> String {
+    let (stacks, instructions) = data;
+    // The crates are moved around, so work on a copy of the stacks
+    let mut stacks = stacks.clone();
 
-    for (moves, from, to) in instructions {
+    for &(moves, from, to) in instructions.iter() {
         for _ in 0..moves {
             //println!("Move {} from {} to {}", moves, from, to);
             //println!("Stacks: {:?}", stacks);
@@ -99,7 +206,7 @@ pub fn day_5_part_1(data: &str) -> String {
                 .get_mut(from - 1)
                 .expect("Unable to get from stack")
                 .pop_back()
-                .expect("Unable to get character from stack");
+                .expect("The heights of the stacks have been checked when parsing");
 
             stacks
                 .get_mut(to - 1)
@@ -112,18 +219,18 @@ pub fn day_5_part_1(data: &str) -> String {
     return get_stacks_signature(&stacks);
 }
 
-pub fn day_5_part_2(data: &str) -> String {
-    let (stacks, instructions) = parse_input_data(data);
-    let mut stacks = stacks;
+pub fn day_5_part_2(data: &(Stacks, Vec<Move>)) -> String {
+    let (stacks, instructions) = data;
+    let mut stacks = stacks.clone();
 
-    for (moves, from, to) in instructions {
+    for &(moves, from, to) in instructions.iter() {
         let mut cratemover_stack: Vec<char> = Vec::new();
 
         let from_stack = stacks.get_mut(from - 1).expect("Unable to get from stack");
         for _ in 0..moves {
             let character = from_stack
                 .pop_back()
-                .expect("Unable to get character from stack");
+                .expect("The heights of the stacks have been checked when parsing");
             cratemover_stack.push(character);
         }
 
@@ -137,26 +244,212 @@ pub fn day_5_part_2(data: &str) -> String {
     return get_stacks_signature(&stacks);
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = DAY;
+    type Parsed = (Stacks, Vec<Move>);
+
+    fn normalize(input: &str) -> Cow<'_, str> {
+        return pad_stacks(input);
+    }
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(data: &Self::Parsed) -> Answer {
+        return day_5_part_1(data).into();
+    }
+
+    fn part_2(data: &Self::Parsed) -> Answer {
+        return day_5_part_2(data).into();
+    }
+}
+
+pub fn register() -> Day {
+    return Day::new::<Day05>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "    [D]    \n\
-[N] [C]    \n\
-[Z] [M] [P]
- 1   2   3 \n\n\
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_05_example.txt");
 
     #[test]
     fn test_day_5_part_1() {
-        assert_eq!(day_5_part_1(EXAMPLE), "CMZ");
+        assert_eq!(day_5_part_1(&parse_input_data(EXAMPLE).unwrap()), "CMZ");
     }
 
     #[test]
     fn test_day_5_part_2() {
-        assert_eq!(day_5_part_2(EXAMPLE), "MCD");
+        assert_eq!(day_5_part_2(&parse_input_data(EXAMPLE).unwrap()), "MCD");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("[A] [B]\n 1   2 ").unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let error = parse_input_data("[A] [b]\n 1   2 \n\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(5, 1, 6, "b", "expected a crate letter or a space")
+        );
+
+        let error = parse_input_data("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 1 from 3 to 2")
+            .unwrap_err();
+        assert_eq!(error.line, 5);
+        assert_eq!(error.message, "expected existing stack numbers");
+
+        // Impossible moves
+        let error = parse_input_data("[A]\n 1 \n\nmove 2 from 1 to 1").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(
+                5,
+                4,
+                1,
+                "move 2 from 1 to 1",
+                "expected at most as many crates as the stack holds"
+            )
+        );
+        let error =
+            parse_input_data("[A]    \n 1   2 \n\nmove 1 from 1 to 2\nmove 0 from 2 to 1")
+                .unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (4, "move 1 from 1 to 2"));
+        assert_eq!(
+            error.message,
+            "expected a crate left on every stack after the moves"
+        );
+        let error = parse_input_data("[A]    \n 1   2 \n\nmove 0 from 1 to 2").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 6, "2"));
+        // A stack emptied then filled again is fine
+        let data =
+            parse_input_data("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 1 from 2 to 1")
+                .unwrap();
+        assert_eq!(day_5_part_1(&data), "AB");
+
+        // Trailing whitespaces removed by an editor
+        let error =
+            parse_input_data("    [D]\n[N] [C]    \n 1   2 \n\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn test_pad_stacks() {
+        assert!(matches!(pad_stacks(EXAMPLE), Cow::Borrowed(_)));
+
+        let stripped = EXAMPLE
+            .lines()
+            .map(|line| line.trim_end())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        assert_eq!(pad_stacks(&stripped), EXAMPLE.trim_end());
+        assert_eq!(day_5_part_1(&parse_input_data(&pad_stacks(&stripped)).unwrap()), "CMZ");
+
+        // Without moves, and with a stack wider than the numbers line
+        assert_eq!(pad_stacks("[A]\n 1"), "[A]\n 1 \n");
+        assert_eq!(pad_stacks("    [B]\n 1   2"), "    [B]\n 1   2 \n");
+    }
+
+    fn stacks_and_moves() -> impl Strategy<Value = (Stacks, Vec<Move>)> {
+        let crates = prop::collection::vec(
+            prop::collection::vec_deque(prop::char::range('A', 'Z'), 1..8),
+            1..10,
+        );
+        return crates.prop_flat_map(|stacks| {
+            let nb_stacks = stacks.len();
+            let moves = prop::collection::vec((1..10usize, 1..=nb_stacks, 1..=nb_stacks), 0..20);
+            return (Just(stacks), moves).prop_map(|(stacks, moves)| {
+                let moves = playable_moves(&stacks, moves);
+                return (stacks, moves);
+            });
+        });
+    }
+
+    /** Takes fewer crates when there aren't enough, so every stack keeps a crate. */
+    fn playable_moves(stacks: &Stacks, moves: Vec<Move>) -> Vec<Move> {
+        let mut heights = stacks.iter().map(|stack| stack.len()).collect::<Vec<usize>>();
+        return moves
+            .into_iter()
+            .map(|(count, from, to)| {
+                let count = count.min(heights[from - 1] - 1);
+                heights[from - 1] -= count;
+                heights[to - 1] += count;
+                return (count, from, to);
+            })
+            .collect();
+    }
+
+    fn to_input(stacks: &Stacks, moves: &[Move]) -> String {
+        let max_height = stacks.iter().map(|stack| stack.len()).max().unwrap();
+        let mut lines = (0..max_height)
+            .rev()
+            .map(|level| {
+                stacks
+                    .iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(character) => format!("[{}]", character),
+                        None => "   ".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>();
+        lines.push(
+            (1..=stacks.len())
+                .map(|number| format!(" {} ", number))
+                .collect::<Vec<String>>()
+                .join(" "),
+        );
+        lines.push(String::new());
+        lines.extend(
+            moves
+                .iter()
+                .map(|(count, from, to)| format!("move {} from {} to {}", count, from, to)),
+        );
+        return lines.join("\n") + "\n";
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip((stacks, moves) in stacks_and_moves()) {
+            let parsed = parse_input_data(&to_input(&stacks, &moves)).unwrap();
+            prop_assert_eq!(parsed, (stacks, moves));
+        }
+
+        #[test]
+        fn prop_signatures_have_a_crate_per_stack(seed: u64, size in 1..100usize) {
+            let input = crate::generate::generate(5, size, seed).unwrap();
+            let data = parse_input_data(&input).unwrap();
+            let nb_stacks = data.0.len();
+            prop_assert_eq!(day_5_part_1(&data).len(), nb_stacks);
+            prop_assert_eq!(day_5_part_2(&data).len(), nb_stacks);
+        }
+
+        #[test]
+        fn prop_single_crate_moves_are_the_same_for_both_cranes(
+            (stacks, moves) in stacks_and_moves()
+        ) {
+            // Only move one crate at a time, from stacks that keep a crate
+            let mut heights = stacks.iter().map(|stack| stack.len()).collect::<Vec<usize>>();
+            let moves = moves
+                .into_iter()
+                .filter(|&(_, from, to)| {
+                    if from == to || heights[from - 1] < 2 {
+                        return false;
+                    }
+                    heights[from - 1] -= 1;
+                    heights[to - 1] += 1;
+                    return true;
+                })
+                .map(|(_, from, to)| (1, from, to))
+                .collect::<Vec<Move>>();
+            let data = (stacks, moves);
+            prop_assert_eq!(day_5_part_1(&data), day_5_part_2(&data));
+        }
     }
 }
@@ -1,7 +1,58 @@
-use core::panic;
+use crate::error::{Error, ParseError};
+use crate::input::InputError;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
+use std::io::{BufRead, Read};
 
-pub fn day_6_part_1(data: &str) -> usize {
-    let characters: Vec<char> = data.chars().collect();
+const DAY: u8 = 6;
+
+/** Without a marker there is no answer, reported at the end of the datastream. */
+fn no_marker(length: usize, marker_size: usize) -> ParseError {
+    let message = format!("expected {} different letters in a row", marker_size);
+    return ParseError::new(DAY, 1, length + 1, "", &message);
+}
+
+fn has_marker(characters: &[char], marker_size: usize) -> bool {
+    return characters.windows(marker_size).any(|window| {
+        let mut hit_table = [false; 26];
+        return window.iter().all(|character| {
+            let index = *character as usize - 'a' as usize;
+            return !std::mem::replace(&mut hit_table[index], true);
+        });
+    });
+}
+
+/** The datastream must have both markers, like with `solve_stream`. */
+pub fn parse_input_data(input: &str) -> Result<Vec<char>, ParseError> {
+    let mut lines = input.lines();
+    let datastream = lines.next().unwrap_or_default();
+    if let Some(line) = lines.next() {
+        return Err(ParseError::new(DAY, 2, 1, line, "expected a single line"));
+    }
+
+    let characters = datastream
+        .chars()
+        .enumerate()
+        .map(|(column, character)| match character {
+            'a'..='z' => Ok(character),
+            _ => Err(ParseError::new(
+                DAY,
+                1,
+                column + 1,
+                &character.to_string(),
+                "expected a lowercase letter",
+            )),
+        })
+        .collect::<Result<Vec<char>, ParseError>>()?;
+    for marker_size in [4, 14] {
+        if !has_marker(&characters, marker_size) {
+            return Err(no_marker(characters.len(), marker_size));
+        }
+    }
+    return Ok(characters);
+}
+
+pub fn day_6_part_1(characters: &[char]) -> usize {
     // iterate over characters with a i variable
     for i in 3..characters.len() {
         let char_n = characters.get(i).expect("Unable to get character");
@@ -22,15 +73,13 @@ pub fn day_6_part_1(data: &str) -> usize {
             return i + 1;
         }
     }
-    panic!("Unable to find a solution");
+    unreachable!("The markers have been checked when parsing");
 }
 
-pub fn day_6_part_2(data: &str) -> usize {
-    let characters: Vec<char> = data.chars().collect();
-
+pub fn day_6_part_2(characters: &[char]) -> usize {
     // This time with 14 characters
 
-    'outer: for i in 14..characters.len() {
+    'outer: for i in 14..=characters.len() {
         let mut hit_table = [false; 26];
         for j in i - 14..i {
             let char_j = characters.get(j).expect("Unable to get character");
@@ -43,34 +92,268 @@ pub fn day_6_part_2(data: &str) -> usize {
         }
         return i;
     }
-    panic!("Unable to find a solution");
+    unreachable!("The markers have been checked when parsing");
+}
+
+/**
+ * Both parts while reading the datastream, which is a single line that can be huge.
+ * A marker is found when the run of different letters ending at a position is long enough.
+ */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(usize, usize), Error> {
+    let read_error = |error| InputError::Read { day: DAY, error };
+
+    // Position right after the last occurrence of each letter
+    let mut last_seen = [0; 26];
+    let mut run_start = 0;
+    let mut position = 0;
+    let mut packet_marker = None;
+    let mut message_marker = None;
+
+    loop {
+        let buffer = reader.fill_buf().map_err(read_error)?;
+        let letters = buffer
+            .iter()
+            .take_while(|byte| byte.is_ascii_lowercase())
+            .count();
+        for &byte in &buffer[..letters] {
+            let letter = (byte - b'a') as usize;
+            run_start = run_start.max(last_seen[letter]);
+            position += 1;
+            last_seen[letter] = position;
+
+            let run = position - run_start;
+            if run >= 4 && packet_marker.is_none() {
+                packet_marker = Some(position);
+            }
+            if run >= 14 && message_marker.is_none() {
+                message_marker = Some(position);
+            }
+        }
+        let end_of_letters = letters < buffer.len() || buffer.is_empty();
+        reader.consume(letters);
+        if end_of_letters {
+            break;
+        }
+    }
+
+    // Trailing whitespaces are trimmed from the whole input, so they are skipped here too
+    let mut first_blank = None;
+    loop {
+        let buffer = reader.fill_buf().map_err(read_error)?;
+        let blanks = buffer
+            .iter()
+            .take_while(|byte| matches!(byte, b' ' | b'\t' | b'\r' | b'\x0b' | b'\x0c'))
+            .count();
+        first_blank = first_blank.or(buffer[..blanks].first().copied());
+        let end_of_blanks = blanks < buffer.len() || buffer.is_empty();
+        reader.consume(blanks);
+        if end_of_blanks {
+            break;
+        }
+    }
+
+    // Only a line ending can follow the letters
+    let mut end = Vec::new();
+    reader.take(4).read_to_end(&mut end).map_err(read_error)?;
+    let after_line = match end.as_slice() {
+        [] => &[][..],
+        [b'\n', after @ ..] => after,
+        _ => {
+            // The whitespaces are part of the line when something follows them
+            let character = match first_blank {
+                Some(blank) => blank as char,
+                None => String::from_utf8_lossy(&end).chars().next().unwrap(),
+            };
+            return Err(ParseError::new(
+                DAY,
+                1,
+                position + 1,
+                &character.to_string(),
+                "expected a lowercase letter",
+            )
+            .into());
+        }
+    };
+    // Except empty lines at the end
+    let mut next_line = after_line.to_vec();
+    let mut line = 2;
+    loop {
+        reader
+            .read_until(b'\n', &mut next_line)
+            .map_err(read_error)?;
+        let content = String::from_utf8_lossy(&next_line);
+        if next_line.is_empty() {
+            break;
+        }
+        if !content.trim_end().is_empty() {
+            return Err(ParseError::new(
+                DAY,
+                line,
+                1,
+                content.trim_end(),
+                "expected a single line",
+            )
+            .into());
+        }
+        next_line.clear();
+        line += 1;
+    }
+
+    return Ok((
+        packet_marker.ok_or_else(|| no_marker(position, 4))?,
+        message_marker.ok_or_else(|| no_marker(position, 14))?,
+    ));
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<char>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(characters: &Self::Parsed) -> Answer {
+        return day_6_part_1(characters).into();
+    }
+
+    fn part_2(characters: &Self::Parsed) -> Answer {
+        return day_6_part_2(characters).into();
+    }
+}
+
+impl StreamingSolution for Day06 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day06>();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE_A: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-    const EXAMPLE_B: &str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-    const EXAMPLE_C: &str = "nppdvjthqldpwncqszvftbrmjlhg";
-    const EXAMPLE_D: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-    const EXAMPLE_E: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+    const EXAMPLE_A: &str = include_str!("../inputs/examples/day_06_example_a.txt");
+    const EXAMPLE_B: &str = include_str!("../inputs/examples/day_06_example_b.txt");
+    const EXAMPLE_C: &str = include_str!("../inputs/examples/day_06_example_c.txt");
+    const EXAMPLE_D: &str = include_str!("../inputs/examples/day_06_example_d.txt");
+    const EXAMPLE_E: &str = include_str!("../inputs/examples/day_06_example_e.txt");
 
     #[test]
     fn test_day_6_part_1() {
-        assert_eq!(day_6_part_1(EXAMPLE_A), 7);
-        assert_eq!(day_6_part_1(EXAMPLE_B), 5);
-        assert_eq!(day_6_part_1(EXAMPLE_C), 6);
-        assert_eq!(day_6_part_1(EXAMPLE_D), 10);
-        assert_eq!(day_6_part_1(EXAMPLE_E), 11);
+        assert_eq!(day_6_part_1(&parse_input_data(EXAMPLE_A).unwrap()), 7);
+        assert_eq!(day_6_part_1(&parse_input_data(EXAMPLE_B).unwrap()), 5);
+        assert_eq!(day_6_part_1(&parse_input_data(EXAMPLE_C).unwrap()), 6);
+        assert_eq!(day_6_part_1(&parse_input_data(EXAMPLE_D).unwrap()), 10);
+        assert_eq!(day_6_part_1(&parse_input_data(EXAMPLE_E).unwrap()), 11);
     }
 
     #[test]
     fn test_day_6_part_2() {
-        assert_eq!(day_6_part_2(EXAMPLE_A), 19);
-        assert_eq!(day_6_part_2(EXAMPLE_B), 23);
-        assert_eq!(day_6_part_2(EXAMPLE_C), 23);
-        assert_eq!(day_6_part_2(EXAMPLE_D), 29);
-        assert_eq!(day_6_part_2(EXAMPLE_E), 26);
+        assert_eq!(day_6_part_2(&parse_input_data(EXAMPLE_A).unwrap()), 19);
+        assert_eq!(day_6_part_2(&parse_input_data(EXAMPLE_B).unwrap()), 23);
+        assert_eq!(day_6_part_2(&parse_input_data(EXAMPLE_C).unwrap()), 23);
+        assert_eq!(day_6_part_2(&parse_input_data(EXAMPLE_D).unwrap()), 29);
+        assert_eq!(day_6_part_2(&parse_input_data(EXAMPLE_E).unwrap()), 26);
+        // The marker is at the very end
+        assert_eq!(
+            day_6_part_2(&parse_input_data("aaaaabcdefghijklmn").unwrap()),
+            18
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("mjqjpq7mgbljsphdztnvjfqwrcgsmlb").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(6, 1, 7, "7", "expected a lowercase letter")
+        );
+        let error = parse_input_data("mjqjpq\nmgbljsphdztnvjfqwrcgsmlb").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let generated = crate::generate::generate(6, 10_000, 0).unwrap();
+        let inputs = [
+            EXAMPLE_A, EXAMPLE_B, EXAMPLE_C, EXAMPLE_D, EXAMPLE_E, &generated,
+        ];
+        for input in inputs {
+            let characters = parse_input_data(input).unwrap();
+            let expected = (day_6_part_1(&characters), day_6_part_2(&characters));
+            // A tiny buffer, so the markers are split between reads
+            let mut reader = std::io::BufReader::with_capacity(3, input.as_bytes());
+            assert_eq!(solve_stream(&mut reader).unwrap(), expected);
+            let with_line_ending = format!("{}\r\n", input.trim_end());
+            assert_eq!(
+                solve_stream(&mut with_line_ending.as_bytes()).unwrap(),
+                expected
+            );
+            let with_empty_lines = format!("{}\n\r\n\n", input.trim_end());
+            assert_eq!(
+                solve_stream(&mut with_empty_lines.as_bytes()).unwrap(),
+                expected
+            );
+            // Split between reads too
+            let with_whitespaces = format!("{} \t \r\n  \n", input.trim_end());
+            let mut reader = std::io::BufReader::with_capacity(3, with_whitespaces.as_bytes());
+            assert_eq!(solve_stream(&mut reader).unwrap(), expected);
+            let with_spaces = format!("{}  ", input.trim_end());
+            assert_eq!(solve_stream(&mut with_spaces.as_bytes()).unwrap(), expected);
+        }
+
+        for input in [
+            "mjqjpq7mgbljsphdztnvjfqwrcgsmlb",
+            "mjqjpqémgbljsphdztnvjfqwrcgsmlb",
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb\nmjqjpq\r\n",
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb \t7",
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb\r7\n",
+        ] {
+            let expected = parse_input_data(&crate::input::normalize(input)).unwrap_err();
+            let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+            assert!(matches!(error, Error::Parse(error) if error == expected));
+        }
+
+        // Valid datastreams without markers
+        for (input, message) in [
+            ("aabbcc\n", "expected 4 different letters in a row"),
+            ("abcdabcd\n", "expected 14 different letters in a row"),
+            ("", "expected 4 different letters in a row"),
+        ] {
+            let expected = ParseError::new(6, 1, input.len().max(1), "", message);
+            assert_eq!(parse_input_data(input).unwrap_err(), expected);
+            let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+            assert!(matches!(error, Error::Parse(error) if error == expected));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_packet_marker_comes_first(datastream in "[a-z]{0,200}") {
+            // Make sure there is a message marker
+            let characters = parse_input_data(&(datastream + "abcdefghijklmn")).unwrap();
+            let part_1 = day_6_part_1(&characters);
+            let part_2 = day_6_part_2(&characters);
+            prop_assert!(part_1 >= 4 && part_1 <= part_2);
+            prop_assert!(part_2 >= 14 && part_2 <= characters.len());
+        }
+
+        #[test]
+        fn prop_generated_inputs(seed: u64, size in 14..500usize) {
+            let input = crate::generate::generate(6, size, seed).unwrap();
+            let characters = parse_input_data(&input).unwrap();
+            // The generated body has at most 3 different letters, so the marker ends near the end
+            let part_2 = day_6_part_2(&characters);
+            prop_assert!(part_2 >= size - 3 && part_2 <= size);
+            prop_assert!(day_6_part_1(&characters) >= 4);
+        }
     }
 }
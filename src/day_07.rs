@@ -1,46 +1,31 @@
-use std::cell::RefCell;
+use crate::error::ParseError;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
 
-#[derive(Debug)]
+/** Folders and files by their full path, such as /a/e and /a/e/i. The root is /. */
+#[derive(Debug, Default)]
 pub struct FileSystem {
-    folders: HashMap<String, Rc<RefCell<Folder>>>,
-    files: HashMap<String, Rc<RefCell<File>>>,
+    pub folders: HashMap<String, Folder>,
+    pub files: HashMap<String, File>,
 }
 
-impl Default for FileSystem {
-    fn default() -> Self {
-        FileSystem {
-            folders: HashMap::new(),
-            files: HashMap::new(),
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Folder {
     // name: String,
-    subfolders: HashSet<String>,
-    files: HashSet<String>,
-}
-
-impl Default for Folder {
-    fn default() -> Self {
-        Folder {
-            // name: "/".to_string(),
-            subfolders: HashSet::new(),
-            files: HashSet::new(),
-        }
-    }
+    /** Full paths of the subfolders */
+    pub subfolders: HashSet<String>,
+    /** Full paths of the files */
+    pub files: HashSet<String>,
 }
 
 #[derive(Debug)]
 pub struct File {
     // name: String,
-    size: usize,
+    pub size: usize,
 }
 
-fn build_full_path(stack: &Vec<String>, name: &str) -> String {
+fn build_full_path(stack: &[String], name: &str) -> String {
     let mut full_path = stack.join("/");
     if full_path != "/" {
         full_path.push('/');
@@ -53,7 +38,7 @@ fn build_full_path(stack: &Vec<String>, name: &str) -> String {
     return full_path;
 }
 
-fn build_current_full_path(stack: &Vec<String>) -> String {
+fn build_current_full_path(stack: &[String]) -> String {
     let mut full_path = stack.join("/");
     if full_path.starts_with("//") {
         full_path = full_path[1..].to_string();
@@ -61,122 +46,154 @@ fn build_current_full_path(stack: &Vec<String>) -> String {
     return full_path;
 }
 
-pub fn parse_input_data(input: &str) -> FileSystem {
+const DAY: u8 = 7;
+const DISK_SIZE: usize = 70000000;
+const MINIMUM_FREE_SIZE: usize = 30000000;
+/** The answers are i64, and the free space is added to the total size */
+const MAXIMUM_TOTAL_SIZE: usize = i64::MAX as usize - MINIMUM_FREE_SIZE;
+
+/**
+ * A folder or file name is a single path component, so a folder can't be its own
+ * subfolder, which would make its size infinite.
+ */
+fn check_name(line_number: usize, line: &str, name: &str) -> Result<(), ParseError> {
+    if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+        return Err(ParseError::at_token(
+            DAY,
+            line_number,
+            line,
+            name,
+            "expected a name without /, other than . and ..",
+        ));
+    }
+    return Ok(());
+}
+
+pub fn parse_input_data(input: &str) -> Result<FileSystem, ParseError> {
     let mut lines = input.lines();
-    let first_line = lines.next().expect("Unable to get first line");
+    let first_line = lines.next().unwrap_or_default();
     if first_line != "$ cd /" {
-        panic!("First line is not cd /");
+        return Err(ParseError::new(DAY, 1, 1, first_line, "expected $ cd /"));
     }
 
     let mut filesystem = FileSystem::default();
     let mut stack: Vec<String> = Vec::new();
     let root = Folder::default();
-    filesystem
-        .folders
-        .insert("/".to_string(), Rc::new(RefCell::new(root)));
+    filesystem.folders.insert("/".to_string(), root);
     stack.push("/".to_string());
+    // The sizes of the files, without those listed twice
+    let mut total_size: usize = 0;
+
+    for (index, line) in lines.enumerate() {
+        // The first line has already been parsed
+        let line_number = index + 2;
+        let error = |message: &str| ParseError::new(DAY, line_number, 1, line, message);
 
-    for line in lines {
-        if line.starts_with("$ cd ") {
-            let folder_name = line.split(" ").nth(2).expect("Unable to get folder name");
-            if folder_name == ".." {
+        if let Some(folder_name) = line.strip_prefix("$ cd ") {
+            if folder_name == "/" {
+                stack.truncate(1);
+            } else if folder_name == ".." {
+                if stack.len() == 1 {
+                    return Err(error("expected a folder to leave, but this is the root"));
+                }
                 stack.pop();
             } else {
+                check_name(line_number, line, folder_name)?;
                 stack.push(folder_name.to_string());
+                if !filesystem
+                    .folders
+                    .contains_key(&build_current_full_path(&stack))
+                {
+                    return Err(error("expected a folder listed by ls"));
+                }
             }
-        } else if line.starts_with("$ ls") {
+        } else if line == "$ ls" {
             // ignore
-        } else if line.starts_with("dir ") {
-            let scan = sscanf::sscanf!(line, "dir {String}").expect("Unable to parse dir line");
-            let full_path = build_full_path(&stack, &scan);
-            let folder = Folder {
-                // name: full_path.clone(),
-                ..Default::default()
-            };
-            filesystem
-                .folders
-                .insert(full_path.clone(), Rc::new(RefCell::new(folder)));
+        } else if let Some(folder_name) = line.strip_prefix("dir ") {
+            check_name(line_number, line, folder_name)?;
+            let full_path = build_full_path(&stack, folder_name);
+            filesystem.folders.entry(full_path.clone()).or_default();
             let current_full_path = build_current_full_path(&stack);
             filesystem
                 .folders
-                .get(&current_full_path)
-                .expect("Unable to get current folder")
-                .borrow_mut()
+                .get_mut(&current_full_path)
+                .expect("The current folder has been checked when entering it")
                 .subfolders
                 .insert(full_path.clone());
         } else {
-            let scan =
-                sscanf::sscanf!(line, "{usize} {String}").expect("Unable to parse file line");
+            let scan = sscanf::sscanf!(line, "{usize} {String}")
+                .map_err(|_| error("expected a file like 14848514 b.txt"))?;
+            check_name(line_number, line, &line[line.len() - scan.1.len()..])?;
             let full_path = build_full_path(&stack, &scan.1);
             let file = File {
                 // name: full_path.clone(),
                 size: scan.0,
             };
-            filesystem
-                .files
-                .insert(full_path.clone(), Rc::new(RefCell::new(file)));
+            if let Some(listed) = filesystem.files.insert(full_path.clone(), file) {
+                total_size -= listed.size;
+            }
+            total_size = total_size
+                .checked_add(scan.0)
+                .filter(|total| *total <= MAXIMUM_TOTAL_SIZE)
+                .ok_or_else(|| error("expected a total size of the files below 2^63"))?;
             let current_full_path = build_current_full_path(&stack);
             filesystem
                 .folders
-                .get(&current_full_path)
-                .expect("Unable to get current folder")
-                .borrow_mut()
+                .get_mut(&current_full_path)
+                .expect("The current folder has been checked when entering it")
                 .files
                 .insert(full_path.clone());
         }
     }
 
-    return filesystem;
+    return Ok(filesystem);
 }
 
-pub fn compute_folder_size(filesystem: &FileSystem, folder: String) -> usize {
+/** The sizes of the parsed files fit, `None` when the sizes of a hand-built file system don't. */
+pub fn compute_folder_size(filesystem: &FileSystem, folder: String) -> Option<usize> {
     let folder = filesystem
         .folders
         .get(&folder)
-        .expect("Unable to get folder")
-        .borrow();
-    let mut size = 0;
+        .expect("Unable to get folder");
+    let mut size: usize = 0;
     for file in folder.files.iter() {
-        size += filesystem
-            .files
-            .get(file)
-            .expect("Unable to get file")
-            .borrow()
-            .size;
+        size = size.checked_add(filesystem.files.get(file).expect("Unable to get file").size)?;
     }
     for subfolder in folder.subfolders.iter() {
-        size += compute_folder_size(filesystem, subfolder.clone());
+        size = size.checked_add(compute_folder_size(filesystem, subfolder.clone())?)?;
     }
-    return size;
+    return Some(size);
 }
 
-pub fn day_7_part_1(data: &str) -> i64 {
-    let data = parse_input_data(data);
+fn folder_size(filesystem: &FileSystem, folder: String) -> usize {
+    return compute_folder_size(filesystem, folder)
+        .expect("The total size has been checked when parsing");
+}
 
+pub fn day_7_part_1(data: &FileSystem) -> i64 {
     let size: usize = data
         .folders
-        .iter()
-        .map(|(name, _)| compute_folder_size(&data, name.clone()))
+        .keys()
+        .map(|name| folder_size(data, name.clone()))
         .filter(|size| *size <= 100000)
         .sum();
 
     return size as i64;
 }
 
-pub fn day_7_part_2(data: &str) -> i64 {
-    let data = parse_input_data(data);
-
-    let disk_size = 70000000;
-    let minimum_free_size = 30000000;
-
-    let total_used_size = compute_folder_size(&data, "/".to_string());
-    let currently_free_size = disk_size - total_used_size;
-    let minimum_to_delete = minimum_free_size - currently_free_size;
+pub fn day_7_part_2(data: &FileSystem) -> i64 {
+    let total_used_size = folder_size(data, "/".to_string());
+    // Nothing has to be deleted when there is already enough free space,
+    // then the smallest folder is enough
+    let minimum_to_delete = total_used_size
+        .checked_add(MINIMUM_FREE_SIZE)
+        .expect("The free space has been checked when parsing")
+        .saturating_sub(DISK_SIZE);
 
     let size: usize = data
         .folders
-        .iter()
-        .map(|(name, _)| compute_folder_size(&data, name.clone()))
+        .keys()
+        .map(|name| folder_size(data, name.clone()))
         .filter(|size| *size >= minimum_to_delete)
         .min()
         .expect("Unable to find minimum size");
@@ -184,50 +201,132 @@ pub fn day_7_part_2(data: &str) -> i64 {
     return size as i64;
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = DAY;
+    type Parsed = FileSystem;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(filesystem: &Self::Parsed) -> Answer {
+        return day_7_part_1(filesystem).into();
+    }
+
+    fn part_2(filesystem: &Self::Parsed) -> Answer {
+        return day_7_part_2(filesystem).into();
+    }
+}
+
+pub fn register() -> Day {
+    return Day::new::<Day07>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k1000";
-
-    const EXAMPLE_2: &str = "$ cd /
-$ ls
-dir a
-42 zzz
-$ cd a
-$ ls
-1000 zzz";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_07_example.txt");
+
+    const EXAMPLE_2: &str = include_str!("../inputs/examples/day_07_example_2.txt");
 
     #[test]
     fn test_day_7_part_1() {
-        assert_eq!(day_7_part_1(EXAMPLE), 95437);
-        assert_eq!(day_7_part_1(EXAMPLE_2), 2042);
+        assert_eq!(day_7_part_1(&parse_input_data(EXAMPLE).unwrap()), 95437);
+        assert_eq!(day_7_part_1(&parse_input_data(EXAMPLE_2).unwrap()), 2042);
     }
 
     #[test]
     fn test_day_7_part_2() {
-        assert_eq!(day_7_part_2(EXAMPLE), 24933642);
+        assert_eq!(day_7_part_2(&parse_input_data(EXAMPLE).unwrap()), 24933642);
+        // Enough free space already, the smallest folder is deleted
+        assert_eq!(day_7_part_2(&parse_input_data(EXAMPLE_2).unwrap()), 1000);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("$ ls\n").unwrap_err();
+        assert_eq!(error, ParseError::new(7, 1, 1, "$ ls", "expected $ cd /"));
+        let error = parse_input_data("$ cd /\n$ ls\ndir a\n$ cd b").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(7, 4, 1, "$ cd b", "expected a folder listed by ls")
+        );
+        let error = parse_input_data("$ cd /\n$ ls\nabc b.txt").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(parse_input_data("$ cd /\n$ cd ..").is_err());
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        // The root listed as its own subfolder used to recurse forever
+        let error = parse_input_data("$ cd /\n$ ls\ndir /\n").unwrap_err();
+        let message = "expected a name without /, other than . and ..";
+        assert_eq!(error, ParseError::new(7, 3, 5, "/", message));
+        for (input, line, column) in [
+            ("$ cd /\n$ ls\ndir a/b", 3, 5),
+            ("$ cd /\n$ ls\ndir ..", 3, 5),
+            ("$ cd /\n$ ls\ndir ", 3, 5),
+            ("$ cd /\n$ ls\n10 .", 3, 4),
+            ("$ cd /\n$ ls\n10 a/b.txt", 3, 4),
+            ("$ cd /\n$ ls\ndir a\n$ cd .", 4, 6),
+            ("$ cd /\n$ ls\ndir a\n$ cd /a", 4, 6),
+        ] {
+            let error = parse_input_data(input).unwrap_err();
+            assert_eq!((error.line, error.column), (line, column), "{}", input);
+            assert_eq!(error.message, message);
+        }
+    }
+
+    #[test]
+    fn test_total_size_overflow() {
+        let error = parse_input_data("$ cd /\n$ ls\n18446744073709551615 a").unwrap_err();
+        assert_eq!(error.line, 3);
+        let big = MAXIMUM_TOTAL_SIZE / 2 + 1;
+        let input = format!("$ cd /\n$ ls\n{} a\ndir b\n$ cd b\n{} c\n", big, big);
+        assert_eq!(parse_input_data(&input).unwrap_err().line, 6);
+        // A file listed twice only counts once
+        let input = format!("$ cd /\n$ ls\n{} a\n$ ls\n{} a\n", big, big);
+        let filesystem = parse_input_data(&input).unwrap();
+        assert_eq!(day_7_part_2(&filesystem), big as i64);
+        let input = format!("$ cd /\n$ ls\n{} a\n", MAXIMUM_TOTAL_SIZE);
+        assert_eq!(
+            day_7_part_2(&parse_input_data(&input).unwrap()),
+            MAXIMUM_TOTAL_SIZE as i64
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_root_size_is_the_sum_of_files(sizes in prop::collection::vec(1..1_000_000usize, 1..20)) {
+            let mut input = "$ cd /\n$ ls\ndir a\n".to_string();
+            for (index, size) in sizes.iter().enumerate() {
+                input.push_str(&format!("{} f{}\n", size, index));
+            }
+            // The same files again in a subfolder
+            input.push_str("$ cd a\n$ ls\n");
+            for (index, size) in sizes.iter().enumerate() {
+                input.push_str(&format!("{} f{}\n", size, index));
+            }
+            let filesystem = parse_input_data(&input).unwrap();
+            let total = sizes.iter().sum::<usize>();
+            prop_assert_eq!(compute_folder_size(&filesystem, "/a".to_string()), Some(total));
+            prop_assert_eq!(compute_folder_size(&filesystem, "/".to_string()), Some(2 * total));
+        }
+
+        #[test]
+        fn prop_generated_inputs(seed: u64, size in 1..200usize) {
+            let input = crate::generate::generate(7, size, seed).unwrap();
+            let filesystem = parse_input_data(&input).unwrap();
+            let root_size = compute_folder_size(&filesystem, "/".to_string()).unwrap() as i64;
+            let part_2 = day_7_part_2(&filesystem);
+            // The deleted folder frees enough space, and is at most the whole disk
+            prop_assert!(part_2 >= root_size - 40_000_000);
+            prop_assert!(part_2 <= root_size);
+            prop_assert!(day_7_part_1(&filesystem) >= 0);
+        }
     }
 }
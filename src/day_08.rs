@@ -1,20 +1,50 @@
+use crate::error::ParseError;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution};
 use ndarray::Array2;
 
-pub fn parse_input_data(input: &str) -> Array2<u8> {
-    let numbers = input
-        .chars()
-        .filter(|c| c.is_digit(10))
-        .map(|c| c.to_digit(10).unwrap() as u8)
-        .collect::<Vec<u8>>();
+const DAY: u8 = 8;
 
-    // compute sqrt of the number of elements
-    let sqrt = (numbers.len() as f64).sqrt() as usize;
+pub fn parse_input_data(input: &str) -> Result<Array2<u8>, ParseError> {
+    let mut numbers = Vec::new();
+    let mut width = 0;
+    let mut height = 0;
 
-    return Array2::from_shape_vec((sqrt, sqrt), numbers).expect("Failed to parse input data");
+    for (index, line) in input.lines().enumerate() {
+        for (column, c) in line.chars().enumerate() {
+            let number = c.to_digit(10).ok_or_else(|| {
+                ParseError::new(
+                    DAY,
+                    index + 1,
+                    column + 1,
+                    &c.to_string(),
+                    "expected a digit",
+                )
+            })?;
+            numbers.push(number as u8);
+        }
+
+        // Every line must have the same number of trees as the first one
+        let line_width = line.chars().count();
+        if index == 0 {
+            width = line_width;
+        } else if line_width != width {
+            return Err(ParseError::new(
+                DAY,
+                index + 1,
+                1,
+                line,
+                "expected as many trees as on the first line",
+            ));
+        }
+        height += 1;
+    }
+
+    return Ok(Array2::from_shape_vec((height, width), numbers)
+        .expect("The number of trees matches the shape"));
 }
 
-pub fn day_8_part_1(data: &str) -> i64 {
-    let trees_heights = parse_input_data(data);
+pub fn day_8_part_1(trees_heights: &Array2<u8>) -> i64 {
     let dim = trees_heights.dim();
     let mut visible_trees: Array2<u8> = Array2::zeros(dim);
 
@@ -82,8 +112,7 @@ pub fn day_8_part_1(data: &str) -> i64 {
     return sum;
 }
 
-pub fn day_8_part_2(data: &str) -> i64 {
-    let trees_height = parse_input_data(data);
+pub fn day_8_part_2(trees_height: &Array2<u8>) -> i64 {
     let dim = trees_height.dim();
 
     //println!("{:?}", trees_height);
@@ -152,23 +181,92 @@ pub fn day_8_part_2(data: &str) -> i64 {
     return max_score;
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = DAY;
+    type Parsed = Array2<u8>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(trees_heights: &Self::Parsed) -> Answer {
+        return day_8_part_1(trees_heights).into();
+    }
+
+    fn part_2(trees_heights: &Self::Parsed) -> Answer {
+        return day_8_part_2(trees_heights).into();
+    }
+}
+
+pub fn register() -> Day {
+    return Day::new::<Day08>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const EXAMPLE: &str = "30373
-25512
-65332
-33549
-35390";
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_08_example.txt");
 
     #[test]
     fn test_day_8_part_1() {
-        assert_eq!(day_8_part_1(EXAMPLE), 21);
+        assert_eq!(day_8_part_1(&parse_input_data(EXAMPLE).unwrap()), 21);
     }
 
     #[test]
     fn test_day_8_part_2() {
-        assert_eq!(day_8_part_2(EXAMPLE), 8);
+        assert_eq!(day_8_part_2(&parse_input_data(EXAMPLE).unwrap()), 8);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("303\n2x5\n653").unwrap_err();
+        assert_eq!(error, ParseError::new(8, 2, 2, "x", "expected a digit"));
+        let error = parse_input_data("303\n25\n653").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(parse_input_data("303\n255").unwrap().dim(), (2, 3));
+    }
+
+    fn grid() -> impl Strategy<Value = Vec<Vec<u8>>> {
+        return (1..20usize, 1..20usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(0..10u8, width), height)
+        });
+    }
+
+    fn to_input(grid: &[Vec<u8>]) -> String {
+        return grid
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|height| height.to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(grid in grid()) {
+            let trees = parse_input_data(&to_input(&grid)).unwrap();
+            prop_assert_eq!(trees.dim(), (grid.len(), grid[0].len()));
+            for ((row, column), height) in trees.indexed_iter() {
+                prop_assert_eq!(grid[row][column], *height);
+            }
+        }
+
+        #[test]
+        fn prop_edges_are_visible(grid in grid()) {
+            let trees = parse_input_data(&to_input(&grid)).unwrap();
+            let (height, width) = trees.dim();
+            let nb_trees = (height * width) as i64;
+            let nb_inner_trees = (height.saturating_sub(2) * width.saturating_sub(2)) as i64;
+            let visible = day_8_part_1(&trees);
+            prop_assert!(visible >= nb_trees - nb_inner_trees && visible <= nb_trees);
+            prop_assert!(day_8_part_2(&trees) >= 0);
+        }
     }
 }
@@ -1,6 +1,11 @@
+use crate::error::{Error, ParseError};
+use crate::input::for_each_line;
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
 use std::collections::HashSet;
+use std::io::BufRead;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -8,34 +13,54 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Instruction {
-    direction: Direction,
-    steps: i64,
+    pub direction: Direction,
+    pub steps: i64,
 }
 
-pub fn parse_input_data(input: &str) -> Vec<Instruction> {
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let direction = match self.direction {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+        };
+        write!(f, "{} {}", direction, self.steps)
+    }
+}
+
+const DAY: u8 = 9;
+
+fn parse_instruction(index: usize, instruction: &str) -> Result<Instruction, ParseError> {
+    let error = |token: &str, message: &str| {
+        ParseError::at_token(DAY, index + 1, instruction, token, message)
+    };
+
+    // Each line contains a letter and a number separated by a space
+    let (direction_str, steps_str) = instruction
+        .split_once(' ')
+        .ok_or_else(|| error(instruction, "expected a direction and a number of steps"))?;
+    let direction = match direction_str {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => return Err(error(direction_str, "expected U, D, L or R")),
+    };
+    // Parse the number
+    let steps = steps_str
+        .parse::<u32>()
+        .map_err(|_| error(steps_str, "expected a number of steps"))? as i64;
+    return Ok(Instruction { direction, steps });
+}
+
+pub fn parse_input_data(input: &str) -> Result<Vec<Instruction>, ParseError> {
     return input
         .lines()
-        .map(|instruction| {
-            // Each line contains a letter and a number separated by a space
-            let mut chars = instruction.chars();
-            let direction = match chars.next().expect("Failed to parse direction") {
-                'U' => Direction::Up,
-                'D' => Direction::Down,
-                'L' => Direction::Left,
-                'R' => Direction::Right,
-                _ => panic!("Failed to parse direction"),
-            };
-            // Skip the space
-            chars.next();
-            // Parse the number
-            let steps = chars
-                .collect::<String>()
-                .parse::<i64>()
-                .expect("Failed to parse steps");
-            return Instruction { direction, steps };
-        })
+        .enumerate()
+        .map(|(index, instruction)| parse_instruction(index, instruction))
         .collect();
 }
 
@@ -45,15 +70,7 @@ pub fn head_next_to_tail(head_position: (i64, i64), tail_position: (i64, i64)) -
 
     // if the head is on the same position as the tail,
     // or if it's on an adjacent cell (north, south, east, west, and diagonals)
-    return head_x == tail_x && head_y == tail_y
-        || head_x == tail_x && head_y == tail_y + 1
-        || head_x == tail_x && head_y == tail_y - 1
-        || head_x == tail_x + 1 && head_y == tail_y
-        || head_x == tail_x - 1 && head_y == tail_y
-        || head_x == tail_x + 1 && head_y == tail_y + 1
-        || head_x == tail_x + 1 && head_y == tail_y - 1
-        || head_x == tail_x - 1 && head_y == tail_y + 1
-        || head_x == tail_x - 1 && head_y == tail_y - 1;
+    return (head_x - tail_x).abs() <= 1 && (head_y - tail_y).abs() <= 1;
 }
 
 pub fn move_tail_towards_head_if_needed(
@@ -107,108 +124,132 @@ pub fn move_tail_towards_head_if_needed(
     return (tail_x - 1, tail_y - 1);
 }
 
-pub fn day_9_part_1(data: &str) -> i64 {
-    let data = parse_input_data(data);
-
-    let mut head_position: (i64, i64) = (0, 0);
-    let mut tail_position: (i64, i64) = (0, 0);
+/** The knots of a rope, starting with the head, and the positions visited by its tail. */
+struct Rope {
+    knots: Vec<(i64, i64)>,
+    visited_positions: HashSet<(i64, i64)>,
+}
 
-    let mut visited_positions: HashSet<(i64, i64)> = HashSet::new();
+impl Rope {
+    /** The tail starts on the origin, which counts as visited even before any motion. */
+    fn new(nb_knots: usize) -> Rope {
+        Rope {
+            knots: vec![(0, 0); nb_knots],
+            visited_positions: HashSet::from([(0, 0)]),
+        }
+    }
 
-    for instruction in data {
+    fn apply(&mut self, instruction: &Instruction) {
         for _ in 0..instruction.steps {
+            let head_position = &mut self.knots[0];
             match instruction.direction {
                 Direction::Up => head_position.1 += 1,
                 Direction::Down => head_position.1 -= 1,
                 Direction::Left => head_position.0 -= 1,
                 Direction::Right => head_position.0 += 1,
             }
-            tail_position = move_tail_towards_head_if_needed(head_position, tail_position);
-            visited_positions.insert(tail_position);
+            // Every knot follows the previous one
+            for knot in 1..self.knots.len() {
+                self.knots[knot] =
+                    move_tail_towards_head_if_needed(self.knots[knot - 1], self.knots[knot]);
+            }
+            self.visited_positions.insert(*self.knots.last().unwrap());
         }
     }
+}
+
+pub fn day_9_part_1(data: &[Instruction]) -> i64 {
+    let mut rope = Rope::new(2);
+    for instruction in data {
+        rope.apply(instruction);
+    }
 
-    return visited_positions.len() as i64;
+    return rope.visited_positions.len() as i64;
 }
 
-pub fn day_9_part_2(data: &str) -> i64 {
-    let data = parse_input_data(data);
+pub fn day_9_part_2(data: &[Instruction]) -> i64 {
+    let mut rope = Rope::new(10);
+    for instruction in data {
+        rope.apply(instruction);
+    }
 
-    let mut visited_positions: HashSet<(i64, i64)> = HashSet::new();
+    return rope.visited_positions.len() as i64;
+}
 
-    let mut head_position: (i64, i64) = (0, 0);
-    let mut knot_1_position: (i64, i64) = (0, 0);
-    let mut knot_2_position: (i64, i64) = (0, 0);
-    let mut knot_3_position: (i64, i64) = (0, 0);
-    let mut knot_4_position: (i64, i64) = (0, 0);
-    let mut knot_5_position: (i64, i64) = (0, 0);
-    let mut knot_6_position: (i64, i64) = (0, 0);
-    let mut knot_7_position: (i64, i64) = (0, 0);
-    let mut knot_8_position: (i64, i64) = (0, 0);
-    let mut tail_position: (i64, i64) = (0, 0);
+/**
+ * Both parts without keeping the motions in memory.
+ * The visited positions are still kept, but they are bounded by the area the rope explores.
+ */
+pub fn solve_stream(reader: &mut dyn BufRead) -> Result<(i64, i64), Error> {
+    let mut short_rope = Rope::new(2);
+    let mut long_rope = Rope::new(10);
+    for_each_line(DAY, reader, |index, instruction| {
+        let instruction = parse_instruction(index, instruction)?;
+        short_rope.apply(&instruction);
+        long_rope.apply(&instruction);
+        return Ok(());
+    })?;
+    return Ok((
+        short_rope.visited_positions.len() as i64,
+        long_rope.visited_positions.len() as i64,
+    ));
+}
 
-    for instruction in data {
-        for _ in 0..instruction.steps {
-            match instruction.direction {
-                Direction::Up => head_position.1 += 1,
-                Direction::Down => head_position.1 -= 1,
-                Direction::Left => head_position.0 -= 1,
-                Direction::Right => head_position.0 += 1,
-            }
-            knot_1_position = move_tail_towards_head_if_needed(head_position, knot_1_position);
-            knot_2_position = move_tail_towards_head_if_needed(knot_1_position, knot_2_position);
-            knot_3_position = move_tail_towards_head_if_needed(knot_2_position, knot_3_position);
-            knot_4_position = move_tail_towards_head_if_needed(knot_3_position, knot_4_position);
-            knot_5_position = move_tail_towards_head_if_needed(knot_4_position, knot_5_position);
-            knot_6_position = move_tail_towards_head_if_needed(knot_5_position, knot_6_position);
-            knot_7_position = move_tail_towards_head_if_needed(knot_6_position, knot_7_position);
-            knot_8_position = move_tail_towards_head_if_needed(knot_7_position, knot_8_position);
-            tail_position = move_tail_towards_head_if_needed(knot_8_position, tail_position);
-            visited_positions.insert(tail_position);
-        }
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = DAY;
+    type Parsed = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        return parse_input_data(input);
+    }
+
+    fn part_1(instructions: &Self::Parsed) -> Answer {
+        return day_9_part_1(instructions).into();
+    }
+
+    fn part_2(instructions: &Self::Parsed) -> Answer {
+        return day_9_part_2(instructions).into();
     }
+}
 
-    return visited_positions.len() as i64;
+impl StreamingSolution for Day09 {
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error> {
+        let (part_1, part_2) = solve_stream(reader)?;
+        return Ok((part_1.into(), part_2.into()));
+    }
+}
+
+pub fn register() -> Day {
+    return Day::streaming::<Day09>();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_09_example.txt");
 
-    const EXAMPLE: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    const LARGER_EXAMPLE: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
+    const LARGER_EXAMPLE: &str = include_str!("../inputs/examples/day_09_larger_example.txt");
 
     #[test]
     fn test_head_next_to_tail() {
-        assert_eq!(head_next_to_tail((2, 1), (1, 1)), true);
-        assert_eq!(head_next_to_tail((3, 1), (1, 1)), false);
-        assert_eq!(head_next_to_tail((3, 1), (2, 1)), true);
+        assert!(head_next_to_tail((2, 1), (1, 1)));
+        assert!(!head_next_to_tail((3, 1), (1, 1)));
+        assert!(head_next_to_tail((3, 1), (2, 1)));
 
-        assert_eq!(head_next_to_tail((1, 2), (1, 1)), true);
-        assert_eq!(head_next_to_tail((1, 3), (1, 1)), false);
-        assert_eq!(head_next_to_tail((1, 3), (1, 2)), true);
+        assert!(head_next_to_tail((1, 2), (1, 1)));
+        assert!(!head_next_to_tail((1, 3), (1, 1)));
+        assert!(head_next_to_tail((1, 3), (1, 2)));
 
-        assert_eq!(head_next_to_tail((2, 2), (1, 3)), true);
-        assert_eq!(head_next_to_tail((2, 1), (1, 3)), false);
-        assert_eq!(head_next_to_tail((2, 1), (2, 2)), true);
+        assert!(head_next_to_tail((2, 2), (1, 3)));
+        assert!(!head_next_to_tail((2, 1), (1, 3)));
+        assert!(head_next_to_tail((2, 1), (2, 2)));
 
-        assert_eq!(head_next_to_tail((3, 2), (1, 3)), false);
-        assert_eq!(head_next_to_tail((3, 2), (2, 2)), true);
+        assert!(!head_next_to_tail((3, 2), (1, 3)));
+        assert!(head_next_to_tail((3, 2), (2, 2)));
     }
 
     #[test]
@@ -231,12 +272,88 @@ U 20";
 
     #[test]
     fn test_day_9_part_1() {
-        assert_eq!(day_9_part_1(EXAMPLE), 13);
+        assert_eq!(day_9_part_1(&parse_input_data(EXAMPLE).unwrap()), 13);
     }
 
     #[test]
     fn test_day_9_part_2() {
-        assert_eq!(day_9_part_2(EXAMPLE), 1);
-        assert_eq!(day_9_part_2(LARGER_EXAMPLE), 36);
+        assert_eq!(day_9_part_2(&parse_input_data(EXAMPLE).unwrap()), 1);
+        assert_eq!(day_9_part_2(&parse_input_data(LARGER_EXAMPLE).unwrap()), 36);
+    }
+
+    #[test]
+    fn test_starting_position_is_visited() {
+        for input in ["", "R 0\n", "R 1\nL 1\n"] {
+            let instructions = parse_input_data(input).unwrap();
+            assert_eq!(day_9_part_1(&instructions), 1, "{:?}", input);
+            assert_eq!(day_9_part_2(&instructions), 1, "{:?}", input);
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), (1, 1));
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_input_data("R 4\nX 4").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(9, 2, 1, "X", "expected U, D, L or R")
+        );
+        let error = parse_input_data("R 4\nU -4").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(9, 2, 3, "-4", "expected a number of steps")
+        );
+        let error = parse_input_data("R4").unwrap_err();
+        assert_eq!(error.message, "expected a direction and a number of steps");
+    }
+
+    fn instructions() -> impl Strategy<Value = Vec<Instruction>> {
+        let direction = prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Down),
+            Just(Direction::Left),
+            Just(Direction::Right),
+        ];
+        let instruction =
+            (direction, 0..30i64).prop_map(|(direction, steps)| Instruction { direction, steps });
+        return prop::collection::vec(instruction, 0..50);
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let generated = crate::generate::generate(9, 1000, 0).unwrap();
+        for input in [EXAMPLE, LARGER_EXAMPLE, &generated] {
+            let instructions = parse_input_data(input).unwrap();
+            let expected = (day_9_part_1(&instructions), day_9_part_2(&instructions));
+            assert_eq!(solve_stream(&mut input.as_bytes()).unwrap(), expected);
+        }
+
+        let input = "R 4\nX 4";
+        let expected = parse_input_data(input).unwrap_err();
+        let error = solve_stream(&mut input.as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(error) if error == expected));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(instructions in instructions()) {
+            let input = instructions
+                .iter()
+                .map(|instruction| instruction.to_string())
+                .collect::<Vec<String>>()
+                .join("\n");
+            prop_assert_eq!(parse_input_data(&input).unwrap(), instructions);
+        }
+
+        #[test]
+        fn prop_tail_visits_are_bounded(instructions in instructions()) {
+            let total_steps = instructions.iter().map(|instruction| instruction.steps).sum::<i64>();
+            let part_1 = day_9_part_1(&instructions);
+            let part_2 = day_9_part_2(&instructions);
+            // The starting position is always visited
+            prop_assert!(part_1 >= 1 && part_1 <= total_steps + 1);
+            // A longer rope can't reach further than a short one
+            prop_assert!(part_2 >= 1 && part_2 <= part_1);
+        }
     }
 }
@@ -0,0 +1,124 @@
+use crate::input::InputError;
+use std::fmt;
+
+/** A puzzle input that doesn't follow the expected format. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /** Starting at 1 */
+    pub line: usize,
+    /** Starting at 1, in characters */
+    pub column: usize,
+    /** The offending text */
+    pub text: String,
+    /** What was expected instead */
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, text: &str, message: &str) -> ParseError {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /** Error about `token`, which must be a slice of `line` so its column can be found. */
+    pub fn at_token(
+        day: u8,
+        line_number: usize,
+        line: &str,
+        token: &str,
+        message: &str,
+    ) -> ParseError {
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(line.as_ptr() as usize)
+            .min(line.len());
+        let column = line
+            .char_indices()
+            .take_while(|(index, _)| *index < offset)
+            .count()
+            + 1;
+        return ParseError::new(day, line_number, column, token, message);
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, line {}, column {}: {}, found \"{}\"",
+            self.day, self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/** Everything that can prevent a day from being solved. */
+#[derive(Debug)]
+pub enum Error {
+    Input(InputError),
+    Parse(ParseError),
+    /** A solution panicked, which is caught so the other days still run */
+    Panic {
+        day: u8,
+        message: String,
+    },
+    /** A day without a `src/day_NN.rs` module */
+    NotImplemented(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Input(error) => write!(f, "{}", error),
+            Error::Parse(error) => write!(f, "{}", error),
+            Error::Panic { day, message } => write!(f, "Day {} panicked: {}", day, message),
+            Error::NotImplemented(day) => write!(f, "Day {} is not implemented", day),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Input(error) => Some(error),
+            Error::Parse(error) => Some(error),
+            Error::Panic { .. } | Error::NotImplemented(_) => None,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(error: InputError) -> Self {
+        Error::Input(error)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_token() {
+        let line = "move 1 from é to 3";
+        let token = &line[12..14];
+        assert_eq!(token, "é");
+        let error = ParseError::at_token(5, 2, line, token, "expected a stack number");
+        assert_eq!(error.column, 13);
+        assert_eq!(
+            error.to_string(),
+            "Day 5, line 2, column 13: expected a stack number, found \"é\""
+        );
+    }
+}
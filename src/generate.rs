@@ -0,0 +1,415 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::io::{BufWriter, Write};
+
+#[derive(Debug)]
+pub enum GenerateError {
+    NoGenerator(u8),
+    /** The inputs of some days can't be valid below a size */
+    TooSmall {
+        day: u8,
+        min_size: usize,
+    },
+    Io(std::io::Error),
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenerateError::NoGenerator(day) => write!(f, "Day {} has no input generator", day),
+            GenerateError::TooSmall { day, min_size } => write!(
+                f,
+                "The inputs of day {} have a size of at least {}",
+                day, min_size
+            ),
+            GenerateError::Io(error) => write!(f, "Unable to write the input: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl From<std::io::Error> for GenerateError {
+    fn from(error: std::io::Error) -> Self {
+        GenerateError::Io(error)
+    }
+}
+
+/** Smallest size of a valid input. */
+pub fn min_size(day: u8) -> usize {
+    return match day {
+        // The empty line between the stacks and the moves is trimmed without moves
+        5 => 1,
+        // Both markers must be found
+        6 => 14,
+        // There must be a file, or there is nothing to delete
+        7 => 1,
+        _ => 0,
+    };
+}
+
+/**
+ * Writes a valid input for a day, from a seed so the same input can be generated again.
+ * The size is the number of elves, games, rucksacks, pairs, moves, characters,
+ * filesystem entries, trees per side, or motions, depending on the day.
+ * The input is written while it's generated, so it can be much bigger than the memory,
+ * except for day 7 whose whole filesystem is generated before being written.
+ */
+pub fn write_input(
+    day: u8,
+    size: usize,
+    seed: u64,
+    writer: impl Write,
+) -> Result<(), GenerateError> {
+    if !(1..=9).contains(&day) {
+        return Err(GenerateError::NoGenerator(day));
+    }
+    if size < min_size(day) {
+        return Err(GenerateError::TooSmall {
+            day,
+            min_size: min_size(day),
+        });
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut writer = BufWriter::new(writer);
+    match day {
+        1 => calories(&mut rng, size, &mut writer),
+        2 => strategy_guide(&mut rng, size, &mut writer),
+        3 => rucksacks(&mut rng, size, &mut writer),
+        4 => section_assignments(&mut rng, size, &mut writer),
+        5 => crate_stacks(&mut rng, size, &mut writer),
+        6 => datastream(&mut rng, size, &mut writer),
+        7 => terminal_output(&mut rng, size, &mut writer),
+        8 => tree_grid(&mut rng, size, &mut writer),
+        _ => rope_motions(&mut rng, size, &mut writer),
+    }?;
+    return Ok(writer.flush()?);
+}
+
+/** The input written by `write_input`, for the inputs which fit in memory. */
+pub fn generate(day: u8, size: usize, seed: u64) -> Result<String, GenerateError> {
+    let mut input = Vec::new();
+    write_input(day, size, seed, &mut input)?;
+    return Ok(String::from_utf8(input).expect("The inputs are ASCII"));
+}
+
+pub fn calories(rng: &mut StdRng, nb_elves: usize, output: &mut dyn Write) -> std::io::Result<()> {
+    for elf in 0..nb_elves {
+        if elf > 0 {
+            writeln!(output)?;
+        }
+        let nb_items = rng.gen_range(1..=15);
+        for _ in 0..nb_items {
+            writeln!(output, "{}", rng.gen_range(1000..=60000))?;
+        }
+    }
+    return Ok(());
+}
+
+pub fn strategy_guide(
+    rng: &mut StdRng,
+    nb_games: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    for _ in 0..nb_games {
+        let opponent = ['A', 'B', 'C'].choose(rng).unwrap();
+        let strategy = ['X', 'Y', 'Z'].choose(rng).unwrap();
+        writeln!(output, "{} {}", opponent, strategy)?;
+    }
+    return Ok(());
+}
+
+/**
+ * The number of rucksacks is rounded up to a multiple of 3 to make groups.
+ * In each group, the rucksacks only share the badge, and the halves of each
+ * rucksack only share one item.
+ */
+pub fn rucksacks(
+    rng: &mut StdRng,
+    nb_rucksacks: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut letters = ('a'..='z').chain('A'..='Z').collect::<Vec<char>>();
+
+    for _ in 0..nb_rucksacks.div_ceil(3) {
+        letters.shuffle(rng);
+        let (badge, others) = letters.split_first().unwrap();
+
+        // Each rucksack of the group gets its own 17 letters
+        for pool in others.chunks(17) {
+            let (common, pool) = pool.split_first().unwrap();
+            let (first_pool, second_pool) = pool.split_at(pool.len() / 2);
+            let half_length = rng.gen_range(2..=16);
+
+            let mut first_half = vec![*common, *badge];
+            let mut second_half = vec![*common];
+            while first_half.len() < half_length {
+                first_half.push(*first_pool.choose(rng).unwrap());
+            }
+            while second_half.len() < half_length {
+                second_half.push(*second_pool.choose(rng).unwrap());
+            }
+            first_half.shuffle(rng);
+            second_half.shuffle(rng);
+
+            let rucksack = first_half
+                .into_iter()
+                .chain(second_half)
+                .collect::<String>();
+            writeln!(output, "{}", rucksack)?;
+        }
+    }
+    return Ok(());
+}
+
+pub fn section_assignments(
+    rng: &mut StdRng,
+    nb_pairs: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut range = || {
+        let start = rng.gen_range(1..=99);
+        let end = rng.gen_range(start..=99);
+        return format!("{}-{}", start, end);
+    };
+
+    for _ in 0..nb_pairs {
+        let first = range();
+        let second = range();
+        writeln!(output, "{},{}", first, second)?;
+    }
+    return Ok(());
+}
+
+/** Moves never empty a stack, so every stack has a crate on top at the end. */
+pub fn crate_stacks(
+    rng: &mut StdRng,
+    nb_moves: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let nb_stacks = 9;
+    let mut heights = (0..nb_stacks)
+        .map(|_| rng.gen_range(1..=8))
+        .collect::<Vec<usize>>();
+    // At least one crate must be movable
+    heights[0] = heights[0].max(2);
+
+    let max_height = *heights.iter().max().unwrap();
+    for level in (0..max_height).rev() {
+        let line = heights
+            .iter()
+            .map(|height| match level < *height {
+                true => format!("[{}]", rng.gen_range('A'..='Z')),
+                false => "   ".to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(output, "{}", line)?;
+    }
+    let numbers = (1..=nb_stacks)
+        .map(|number| format!(" {} ", number))
+        .collect::<Vec<String>>()
+        .join(" ");
+    writeln!(output, "{}\n", numbers)?;
+
+    for _ in 0..nb_moves {
+        let movable = (0..nb_stacks)
+            .filter(|stack| heights[*stack] > 1)
+            .collect::<Vec<usize>>();
+        let from = *movable.choose(rng).unwrap();
+        let to = (from + rng.gen_range(1..nb_stacks)) % nb_stacks;
+        let count = rng.gen_range(1..heights[from]);
+        heights[from] -= count;
+        heights[to] += count;
+        writeln!(output, "move {} from {} to {}", count, from + 1, to + 1)?;
+    }
+    return Ok(());
+}
+
+/**
+ * Made of only 3 different letters, so the markers are only found
+ * near the end of the datastream, among the last 14 different letters.
+ */
+pub fn datastream(rng: &mut StdRng, length: usize, output: &mut dyn Write) -> std::io::Result<()> {
+    let mut marker = ('a'..='z').collect::<Vec<char>>();
+    marker.shuffle(rng);
+    marker.truncate(14);
+
+    for _ in 0..length.saturating_sub(marker.len()) {
+        write!(output, "{}", rng.gen_range('a'..='c'))?;
+    }
+    return writeln!(output, "{}", marker.into_iter().collect::<String>());
+}
+
+/**
+ * A random tree of folders and files, explored depth first.
+ * The total size is between 45 and 65 million, so there is something to delete.
+ * Only the tree is kept in memory, not the output.
+ */
+pub fn terminal_output(
+    rng: &mut StdRng,
+    nb_entries: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    // The sizes of the files depend on the total weight, so the whole tree is kept in memory.
+    // Folders are their list of subfolders and their list of file weights
+    let mut folders: Vec<(Vec<usize>, Vec<u64>)> = vec![(Vec::new(), Vec::new())];
+    // The first entry is a file, so the disk isn't empty
+    for index in 0..nb_entries {
+        let parent = rng.gen_range(0..folders.len());
+        if index > 0 && rng.gen_bool(0.3) {
+            folders.push((Vec::new(), Vec::new()));
+            let folder = folders.len() - 1;
+            folders[parent].0.push(folder);
+        } else {
+            let weight = rng.gen_range(1..=1000);
+            folders[parent].1.push(weight);
+        }
+    }
+
+    let total_weight: u64 = folders.iter().flat_map(|(_, files)| files).sum();
+    let total_size = rng.gen_range(45_000_000..=65_000_000);
+
+    enum Step {
+        Enter(usize),
+        List(usize),
+        Leave,
+    }
+
+    writeln!(output, "$ cd /")?;
+    let mut steps = vec![Step::List(0)];
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Enter(folder) => writeln!(output, "$ cd d{}", folder)?,
+            Step::Leave => writeln!(output, "$ cd ..")?,
+            Step::List(folder) => {
+                let (subfolders, files) = &folders[folder];
+                writeln!(output, "$ ls")?;
+                for subfolder in subfolders {
+                    writeln!(output, "dir d{}", subfolder)?;
+                }
+                for (index, weight) in files.iter().enumerate() {
+                    let size = weight * total_size / total_weight;
+                    writeln!(output, "{} f{}.txt", size, index)?;
+                }
+
+                // Reversed, as the last pushed step is the first done
+                for subfolder in subfolders.iter().rev() {
+                    steps.push(Step::Leave);
+                    steps.push(Step::List(*subfolder));
+                    steps.push(Step::Enter(*subfolder));
+                }
+            }
+        }
+    }
+    return Ok(());
+}
+
+pub fn tree_grid(rng: &mut StdRng, side: usize, output: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..side {
+        let row = (0..side)
+            .map(|_| char::from_digit(rng.gen_range(0..10), 10).unwrap())
+            .collect::<String>();
+        writeln!(output, "{}", row)?;
+    }
+    return Ok(());
+}
+
+pub fn rope_motions(
+    rng: &mut StdRng,
+    nb_motions: usize,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    for _ in 0..nb_motions {
+        let direction = ['U', 'D', 'L', 'R'].choose(rng).unwrap();
+        writeln!(output, "{} {}", direction, rng.gen_range(1..=20))?;
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+    use crate::{day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09};
+
+    fn check<S: Solution>(size: usize) {
+        for seed in 0..5 {
+            // Normalised like the inputs read by the runner
+            let input = crate::input::normalize(&generate(S::DAY, size, seed).unwrap());
+            let parsed = S::parse(&S::normalize(&input)).unwrap();
+            // Solving shouldn't panic
+            S::part_1(&parsed);
+            S::part_2(&parsed);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_are_valid() {
+        check::<day_01::Day01>(100);
+        check::<day_02::Day02>(100);
+        check::<day_03::Day03>(100);
+        check::<day_04::Day04>(100);
+        check::<day_05::Day05>(100);
+        check::<day_06::Day06>(100);
+        check::<day_07::Day07>(100);
+        check::<day_08::Day08>(20);
+        check::<day_09::Day09>(100);
+    }
+
+    #[test]
+    fn test_generate_is_seeded() {
+        assert_eq!(generate(5, 10, 42).unwrap(), generate(5, 10, 42).unwrap());
+        assert_ne!(generate(5, 10, 42).unwrap(), generate(5, 10, 43).unwrap());
+        assert!(matches!(
+            generate(42, 10, 42),
+            Err(GenerateError::NoGenerator(42))
+        ));
+    }
+
+    #[test]
+    fn test_smallest_inputs_are_valid() {
+        check::<day_01::Day01>(min_size(1));
+        check::<day_02::Day02>(min_size(2));
+        check::<day_03::Day03>(min_size(3));
+        check::<day_04::Day04>(min_size(4));
+        check::<day_05::Day05>(min_size(5));
+        check::<day_06::Day06>(min_size(6));
+        check::<day_07::Day07>(min_size(7));
+        check::<day_08::Day08>(min_size(8));
+        check::<day_09::Day09>(min_size(9));
+        for day in [5, 6, 7] {
+            assert!(matches!(
+                generate(day, min_size(day) - 1, 0),
+                Err(GenerateError::TooSmall { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_write_input() {
+        // Written in small pieces, the same as in memory
+        let mut output = Vec::new();
+        write_input(7, 500, 3, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            generate(7, 500, 3).unwrap()
+        );
+
+        // The errors of the writer are returned
+        let mut full = [0u8; 10];
+        let error = write_input(2, 100, 0, &mut full[..]).unwrap_err();
+        assert!(matches!(error, GenerateError::Io(_)));
+    }
+
+    #[test]
+    fn test_generated_sizes() {
+        assert_eq!(generate(2, 10, 0).unwrap().lines().count(), 10);
+        assert_eq!(generate(3, 10, 0).unwrap().lines().count(), 12);
+        assert_eq!(generate(6, 100, 0).unwrap().trim_end().len(), 100);
+        assert_eq!(generate(8, 7, 0).unwrap().lines().count(), 7);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::error::{Error, ParseError};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/** Where the puzzle input of a day comes from. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /** `day_NN.txt` in the inputs folder */
+    Default,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl From<&str> for InputSource {
+    fn from(argument: &str) -> Self {
+        match argument {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(PathBuf::from(path)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound {
+        day: u8,
+        path: PathBuf,
+    },
+    Io {
+        day: u8,
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Stdin {
+        day: u8,
+        error: std::io::Error,
+    },
+    /** While streaming the input */
+    Read {
+        day: u8,
+        error: std::io::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::NotFound { day, path } => {
+                write!(f, "Day {}: input file {} not found", day, path.display())
+            }
+            InputError::Io { day, path, error } => write!(
+                f,
+                "Day {}: unable to read input file {}: {}",
+                day,
+                path.display(),
+                error
+            ),
+            InputError::Stdin { day, error } => {
+                write!(f, "Day {}: unable to read input from stdin: {}", day, error)
+            }
+            InputError::Read { day, error } => {
+                write!(f, "Day {}: unable to read input: {}", day, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+pub fn default_input_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    return inputs_dir.join(format!("day_{:02}.txt", day));
+}
+
+const BOM: char = '\u{feff}';
+
+/**
+ * Undoes what editors and other operating systems do to the inputs:
+ * the UTF-8 byte order mark, CRLF line endings, trailing whitespaces,
+ * and the missing or extra newlines at the end. The input then ends with a single newline.
+ */
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+    let mut normalized = String::with_capacity(input.len() + 1);
+    for line in input.trim_end().lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    return normalized;
+}
+
+/** Loads the input of a day, normalized. */
+pub fn load_input(day: u8, source: &InputSource, inputs_dir: &Path) -> Result<String, InputError> {
+    let path = match source {
+        InputSource::Default => default_input_path(inputs_dir, day),
+        InputSource::Path(path) => path.clone(),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|error| InputError::Stdin { day, error })?;
+            return Ok(normalize(&input));
+        }
+    };
+
+    return match std::fs::read_to_string(&path) {
+        Ok(input) => Ok(normalize(&input)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            Err(InputError::NotFound { day, path })
+        }
+        Err(error) => Err(InputError::Io { day, path, error }),
+    };
+}
+
+/** Skips the UTF-8 byte order mark at the start of a reader, if there is one. */
+fn skip_bom(day: u8, mut reader: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, InputError> {
+    let mut bom = [0; 3];
+    BOM.encode_utf8(&mut bom);
+    let start = reader
+        .fill_buf()
+        .map_err(|error| InputError::Read { day, error })?;
+    if start.starts_with(&bom) {
+        reader.consume(bom.len());
+    }
+    return Ok(reader);
+}
+
+/**
+ * Like `load_input`, but the input is read as it is consumed.
+ * Only the byte order mark is removed here, `for_each_line` takes care of the rest.
+ */
+pub fn open_input(
+    day: u8,
+    source: &InputSource,
+    inputs_dir: &Path,
+) -> Result<Box<dyn BufRead>, InputError> {
+    let path = match source {
+        InputSource::Default => default_input_path(inputs_dir, day),
+        InputSource::Path(path) => path.clone(),
+        InputSource::Stdin => return skip_bom(day, Box::new(std::io::stdin().lock())),
+    };
+
+    let reader: Box<dyn BufRead> = match std::fs::File::open(&path) {
+        Ok(file) => Box::new(BufReader::new(file)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(InputError::NotFound { day, path })
+        }
+        Err(error) => return Err(InputError::Io { day, path, error }),
+    };
+    return skip_bom(day, reader);
+}
+
+/**
+ * Calls `parse_line` with the index and content of every line, normalized like `normalize` does:
+ * without their line ending and trailing whitespaces, and without the empty lines at the end.
+ * The same buffer is reused, so only one line is in memory at a time.
+ */
+pub fn for_each_line(
+    day: u8,
+    reader: &mut dyn BufRead,
+    mut parse_line: impl FnMut(usize, &str) -> Result<(), ParseError>,
+) -> Result<(), Error> {
+    let mut line = String::new();
+    let mut index = 0;
+    // The empty lines are only given once a non empty line follows them
+    let mut empty_lines = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|error| InputError::Read { day, error })?;
+        if read == 0 {
+            return Ok(());
+        }
+
+        let content = line.trim_end();
+        if content.is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        for _ in 0..empty_lines {
+            parse_line(index, "")?;
+            index += 1;
+        }
+        empty_lines = 0;
+        parse_line(index, content)?;
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_input_path() {
+        assert_eq!(
+            default_input_path(Path::new("inputs"), 7),
+            PathBuf::from("inputs/day_07.txt")
+        );
+    }
+
+    #[test]
+    fn test_load_input() {
+        let inputs_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let input = load_input(4, &InputSource::Default, &inputs_dir).unwrap();
+        assert!(!input.is_empty());
+
+        let missing = load_input(42, &InputSource::Default, &inputs_dir);
+        assert!(matches!(missing, Err(InputError::NotFound { day: 42, .. })));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize("\u{feff}a\nb"), "a\nb\n");
+        assert_eq!(normalize("a  \n\nb\t\n\n\n"), "a\n\nb\n");
+        assert_eq!(normalize("\r\n"), "");
+        // Only the trailing whitespaces are removed
+        assert_eq!(normalize("  a\n    [D]"), "  a\n    [D]\n");
+    }
+
+    #[test]
+    fn test_skip_bom() {
+        let mut reader = skip_bom(1, Box::new("\u{feff}a\n".as_bytes())).unwrap();
+        let mut input = String::new();
+        reader.read_to_string(&mut input).unwrap();
+        assert_eq!(input, "a\n");
+    }
+
+    #[test]
+    fn test_for_each_line() {
+        let input = "first\r\n\nthird  \r\nlast\r\n\r\n\n";
+        let mut lines = Vec::new();
+        for_each_line(1, &mut input.as_bytes(), |index, line| {
+            lines.push((index, line.to_string()));
+            return Ok(());
+        })
+        .unwrap();
+        let normalized = normalize(input);
+        let expected = normalized.lines().enumerate();
+        assert_eq!(
+            lines,
+            expected
+                .map(|(i, l)| (i, l.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let error = for_each_line(1, &mut "a\nb".as_bytes(), |index, line| {
+            return Err(ParseError::new(1, index + 1, 1, line, "expected nothing"));
+        })
+        .unwrap_err();
+        assert!(matches!(error, Error::Parse(ParseError { line: 1, .. })));
+    }
+}
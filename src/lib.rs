@@ -0,0 +1,25 @@
+//! Solutions of the 2022 advent of code, usable without the command line interface.
+//!
+//! ```
+//! use advent_of_code_2022_rust::registry;
+//! use advent_of_code_2022_rust::solution::Answer;
+//!
+//! let day_4 = registry::find(4).unwrap();
+//! let run = (day_4.run)("2-4,6-8\n2-8,3-7", &[1, 2], 1).unwrap();
+//! assert_eq!(run.parts[0].answer, Answer::Integer(1));
+//! ```
+
+// The day_NN modules, found by build.rs
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+pub mod answers;
+pub mod cache;
+pub mod error;
+pub mod generate;
+pub mod input;
+pub mod rebalance;
+pub mod registry;
+pub mod report;
+pub mod rps;
+pub mod runner;
+pub mod solution;
@@ -1,47 +1,292 @@
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-
-fn main() {
-    let day_1_data = include_str!("../inputs/day_01.txt");
-    println!("Day 1, part 1: {}", day_01::day_1_part_1(day_1_data));
-    println!("Day 1, part 2: {}", day_01::day_1_part_2(day_1_data));
-
-    let day_2_data = include_str!("../inputs/day_02.txt");
-    println!("Day 2, part 1: {}", day_02::day_2_part_1(day_2_data));
-    println!("Day 2, part 2: {}", day_02::day_2_part_2(day_2_data));
-
-    let day_3_data = include_str!("../inputs/day_03.txt");
-    println!("Day 3, part 1: {}", day_03::day_3_part_1(day_3_data));
-    println!("Day 3, part 2: {}", day_03::day_3_part_2(day_3_data));
-
-    let day_4_data = include_str!("../inputs/day_04.txt");
-    println!("Day 4, part 1: {}", day_04::day_4_part_1(day_4_data));
-    println!("Day 4, part 2: {}", day_04::day_4_part_2(day_4_data));
-
-    let day_5_data = include_str!("../inputs/day_05.txt");
-    println!("Day 5, part 1: {}", day_05::day_5_part_1(day_5_data));
-    println!("Day 5, part 2: {}", day_05::day_5_part_2(day_5_data));
-
-    let day_6_data = include_str!("../inputs/day_06.txt");
-    println!("Day 6, part 1: {}", day_06::day_6_part_1(day_6_data));
-    println!("Day 6, part 2: {}", day_06::day_6_part_2(day_6_data));
-
-    let day_7_data = include_str!("../inputs/day_07.txt");
-    println!("Day 7, part 1: {}", day_07::day_7_part_1(day_7_data));
-    println!("Day 7, part 2: {}", day_07::day_7_part_2(day_7_data));
-
-    let day_8_data = include_str!("../inputs/day_08.txt");
-    println!("Day 8, part 1: {}", day_08::day_8_part_1(day_8_data));
-    println!("Day 8, part 2: {}", day_08::day_8_part_2(day_8_data));
-
-    let day_9_data = include_str!("../inputs/day_09.txt");
-    println!("Day 9, part 1: {}", day_09::day_9_part_1(day_9_data));
-    println!("Day 9, part 2: {}", day_09::day_9_part_2(day_9_data));
+mod cli;
+mod new_day;
+mod watch;
+
+use advent_of_code_2022_rust::runner::{self, RunOptions};
+use advent_of_code_2022_rust::{
+    answers, cache, day_01, error, generate, input, rebalance, registry, report, solution,
+};
+use answers::ExpectedAnswers;
+use cache::Cache;
+use clap::Parser;
+use cli::{
+    CacheArgs, Cli, Command, Format, GenerateArgs, NewDayArgs, RebalanceArgs, RunArgs, StatsArgs,
+    VerifyArgs, WatchArgs,
+};
+use error::Error;
+use input::InputSource;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/** The cache of the answers, only with --cached. Errors are printed. */
+fn load_cache(args: &CacheArgs) -> Result<Option<Cache>, ()> {
+    if !args.cached {
+        return Ok(None);
+    }
+    return Cache::load(&args.cache_file).map(Some).map_err(|error| {
+        eprintln!("{}", error);
+    });
+}
+
+fn save_cache(cache: Option<&Cache>) -> ExitCode {
+    if let Some(Err(error)) = cache.map(Cache::save) {
+        eprintln!("{}", error);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run(args: &RunArgs) -> ExitCode {
+    // Complain about the missing days before running anything
+    let days = match registry::select(args.days.day.as_ref()) {
+        Ok(days) => days,
+        Err(missing) => {
+            for error in missing {
+                eprintln!("{}", error);
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.stream {
+        let not_streaming = days
+            .iter()
+            .filter(|day| day.stream.is_none())
+            .collect::<Vec<_>>();
+        if !not_streaming.is_empty() {
+            for day in not_streaming {
+                eprintln!("Day {} can't be solved while streaming its input", day.day);
+            }
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let source = match &args.input {
+        Some(_) if days.len() != 1 => {
+            eprintln!("--input can only be used when running a single day");
+            return ExitCode::FAILURE;
+        }
+        Some(argument) => InputSource::from(argument.as_str()),
+        None => InputSource::Default,
+    };
+
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let Ok(cache) = load_cache(&args.cache) else {
+        return ExitCode::FAILURE;
+    };
+    let options = RunOptions {
+        source: &source,
+        inputs_dir: &args.inputs_dir,
+        parts: &parts,
+        repeat: args.repeat(),
+        stream: args.stream,
+        jobs: args.jobs.get(),
+    };
+    let solved = runner::solve_days(&days, &options, cache.as_ref());
+    if save_cache(cache.as_ref()) == ExitCode::FAILURE {
+        return ExitCode::FAILURE;
+    }
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut runs = Vec::new();
+    for solved in solved {
+        let run = match solved {
+            Ok(run) => run,
+            Err(error) => {
+                eprintln!("{}", error);
+                exit_code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        if args.format == Format::Text {
+            report::print_answers(&run);
+        }
+        runs.push(run);
+    }
+
+    let output = std::io::stdout().lock();
+    let written = match args.format {
+        Format::Text if args.time => report::write_timings(&runs, output),
+        Format::Text => Ok(()),
+        Format::Json => report::write_json(&runs, output),
+        Format::Csv => report::write_csv(&runs, output),
+    };
+    if let Err(error) = written {
+        eprintln!("Unable to write the report: {}", error);
+        return ExitCode::FAILURE;
+    }
+
+    return exit_code;
+}
+
+fn verify(args: &VerifyArgs) -> ExitCode {
+    let Ok(cache) = load_cache(&args.cache) else {
+        return ExitCode::FAILURE;
+    };
+    let jobs = args.jobs.get();
+    let verifications = match args.examples {
+        true => answers::load_examples(&args.examples_dir).map(|examples| {
+            answers::verify_examples(&examples, &args.examples_dir, jobs, cache.as_ref())
+        }),
+        false => ExpectedAnswers::load(&args.answers).map(|expected| {
+            answers::verify_inputs(&expected, &args.inputs_dir, jobs, cache.as_ref())
+        }),
+    };
+    let verifications = match verifications {
+        Ok(verifications) => verifications,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    if save_cache(cache.as_ref()) == ExitCode::FAILURE {
+        return ExitCode::FAILURE;
+    }
+
+    let mut failures = 0;
+    for verification in verifications.iter() {
+        report::print_verification(verification);
+        failures += verification.failures();
+    }
+    if failures > 0 {
+        eprintln!("{} failure(s)", failures);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn generate(args: &GenerateArgs) -> ExitCode {
+    let written = match &args.output {
+        Some(path) => std::fs::File::create(path)
+            .map_err(generate::GenerateError::Io)
+            .and_then(|file| generate::write_input(args.day, args.size, args.seed, file)),
+        None => generate::write_input(args.day, args.size, args.seed, std::io::stdout().lock()),
+    };
+    if let Err(error) = written {
+        eprintln!("{}", error);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn watch(args: &WatchArgs) -> ExitCode {
+    let Some(day) = registry::find(args.day) else {
+        eprintln!("Day {} is not implemented", args.day);
+        return ExitCode::FAILURE;
+    };
+
+    let mut files = vec![(
+        "input",
+        input::default_input_path(&args.inputs_dir, day.day),
+    )];
+    if let Some(example) = &args.example {
+        files.push(("example", example.clone()));
+    }
+    watch::watch(
+        &day,
+        files,
+        &args.inputs_dir,
+        Duration::from_millis(args.interval),
+    );
+}
+
+fn new_day(args: &NewDayArgs) -> ExitCode {
+    return match new_day::create_day(
+        args.day,
+        &args.src_dir,
+        &args.inputs_dir,
+        &args.examples_dir,
+    ) {
+        Ok(created) => {
+            println!(
+                "Created {}, {} and {}",
+                created.module.display(),
+                created.input.display(),
+                created.example.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    };
+}
+
+/** The inventories of day 1, errors are printed. */
+fn load_elves(input: &Option<String>, inputs_dir: &Path) -> Option<Vec<Vec<day_01::Calories>>> {
+    let source = match input {
+        Some(argument) => InputSource::from(argument.as_str()),
+        None => InputSource::Default,
+    };
+    let elves = input::load_input(1, &source, inputs_dir)
+        .map_err(Error::from)
+        .and_then(|data| Ok(day_01::parse_input_data(&data)?));
+    return elves
+        .map_err(|error| {
+            eprintln!("{}", error);
+        })
+        .ok();
+}
+
+fn stats(args: &StatsArgs) -> ExitCode {
+    let Some(elves) = load_elves(&args.input, &args.inputs_dir) else {
+        return ExitCode::FAILURE;
+    };
+
+    let statistics = day_01::statistics(&elves, args.buckets.get());
+    let output = std::io::stdout().lock();
+    let written = match args.format {
+        Format::Text => report::write_statistics(&statistics, output),
+        Format::Json => report::write_statistics_json(&statistics, output),
+        Format::Csv => report::write_statistics_csv(&statistics, output),
+    };
+    if let Err(error) = written {
+        eprintln!("Unable to write the statistics: {}", error);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn rebalance(args: &RebalanceArgs) -> ExitCode {
+    let Some(elves) = load_elves(&args.input, &args.inputs_dir) else {
+        return ExitCode::FAILURE;
+    };
+
+    let nb_elves = args
+        .elves
+        .unwrap_or(NonZeroUsize::new(elves.len()).unwrap_or(NonZeroUsize::MIN));
+    let plan = rebalance::rebalance(&elves, nb_elves);
+    let output = std::io::stdout().lock();
+    let written = match args.format {
+        Format::Text => report::write_plan(&plan, output),
+        Format::Json => report::write_plan_json(&plan, output),
+        Format::Csv => report::write_plan_csv(&plan, output),
+    };
+    if let Err(error) = written {
+        eprintln!("Unable to write the plan: {}", error);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    return match cli.command {
+        Some(Command::Run(args)) => run(&args),
+        Some(Command::Verify(args)) => verify(&args),
+        Some(Command::Generate(args)) => generate(&args),
+        Some(Command::Watch(args)) => watch(&args),
+        Some(Command::NewDay(args)) => new_day(&args),
+        Some(Command::Stats(args)) => stats(&args),
+        Some(Command::Rebalance(args)) => rebalance(&args),
+        None => run(&RunArgs::default()),
+    };
 }
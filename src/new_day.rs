@@ -0,0 +1,178 @@
+use crate::answers::DEFAULT_ANSWERS_FILE;
+use crate::input::default_input_path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.template");
+
+/** The example of a new day, whose answers are the ones of the template stubs. */
+const EXAMPLE: &str = "first line\nsecond line\n";
+const EXAMPLE_ANSWERS: &str = "part_1 = 2\npart_2 = 2\n";
+
+#[derive(Debug)]
+pub enum NewDayError {
+    /** Advent of code only has 25 days */
+    InvalidDay(u8),
+    AlreadyExists(PathBuf),
+    Io(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for NewDayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NewDayError::InvalidDay(day) => {
+                write!(f, "Day {} doesn't exist, days go from 1 to 25", day)
+            }
+            NewDayError::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            NewDayError::Io(path, error) => {
+                write!(f, "Unable to write {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NewDayError {}
+
+/** The source of a new day module, with stubs for the parsing and both parts. */
+pub fn render_template(day: u8) -> String {
+    return TEMPLATE
+        .replace("{{DAY_PADDED}}", &format!("{:02}", day))
+        .replace("{{DAY}}", &day.to_string());
+}
+
+/** The files written for a new day. */
+#[derive(Debug)]
+pub struct NewDay {
+    pub module: PathBuf,
+    pub input: PathBuf,
+    pub example: PathBuf,
+}
+
+/**
+ * Adds the answers of the example to the answers.toml file of the examples,
+ * unless the example already has some.
+ */
+fn add_example_answers(examples_dir: &Path, name: &str) -> Result<(), NewDayError> {
+    let path = examples_dir.join(DEFAULT_ANSWERS_FILE);
+    let io_error = |error| NewDayError::Io(path.clone(), error);
+    let mut content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(io_error(error)),
+    };
+    let table = format!("[{}]", name);
+    if content.lines().any(|line| line.trim() == table) {
+        return Ok(());
+    }
+
+    if !content.is_empty() {
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+    content.push_str(&format!("{}\n{}", table, EXAMPLE_ANSWERS));
+    return std::fs::write(&path, content).map_err(io_error);
+}
+
+/**
+ * Writes the module of a new day, its empty input file and its example with answers.
+ * The module is registered by the build script, so nothing else has to be edited.
+ * Its tests include the example from inputs/examples, like the other days.
+ */
+pub fn create_day(
+    day: u8,
+    src_dir: &Path,
+    inputs_dir: &Path,
+    examples_dir: &Path,
+) -> Result<NewDay, NewDayError> {
+    if !(1..=25).contains(&day) {
+        return Err(NewDayError::InvalidDay(day));
+    }
+
+    let module_path = src_dir.join(format!("day_{:02}.rs", day));
+    if module_path.exists() {
+        return Err(NewDayError::AlreadyExists(module_path));
+    }
+    std::fs::write(&module_path, render_template(day))
+        .map_err(|error| NewDayError::Io(module_path.clone(), error))?;
+
+    // An existing input is kept, it may already have been downloaded
+    let input_path = default_input_path(inputs_dir, day);
+    if !input_path.exists() {
+        std::fs::create_dir_all(inputs_dir)
+            .and_then(|_| std::fs::write(&input_path, ""))
+            .map_err(|error| NewDayError::Io(input_path.clone(), error))?;
+    }
+
+    // Same for an existing example
+    let name = format!("day_{:02}_example", day);
+    let example_path = examples_dir.join(format!("{}.txt", name));
+    if !example_path.exists() {
+        std::fs::create_dir_all(examples_dir)
+            .and_then(|_| std::fs::write(&example_path, EXAMPLE))
+            .map_err(|error| NewDayError::Io(example_path.clone(), error))?;
+    }
+    add_example_answers(examples_dir, &name)?;
+
+    return Ok(NewDay {
+        module: module_path,
+        input: input_path,
+        example: example_path,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let source = render_template(10);
+        assert!(source.contains("const DAY: u8 = 10;"));
+        assert!(source.contains("pub fn day_10_part_1("));
+        assert!(source.contains("impl Solution for Day10 {"));
+        assert!(!source.contains("{{"));
+        assert!(render_template(3).contains("pub struct Day03;"));
+    }
+
+    #[test]
+    fn test_create_day() {
+        let dir = std::env::temp_dir().join(format!("new_day_{}", std::process::id()));
+        let (src_dir, inputs_dir) = (dir.join("src"), dir.join("inputs"));
+        let examples_dir = inputs_dir.join("examples");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::create_dir_all(&examples_dir).unwrap();
+        let answers_path = examples_dir.join(DEFAULT_ANSWERS_FILE);
+        std::fs::write(&answers_path, "[day_01_example]\npart_1 = 24000\n").unwrap();
+        std::fs::write(examples_dir.join("day_01_example.txt"), "24000\n").unwrap();
+
+        let created = create_day(12, &src_dir, &inputs_dir, &examples_dir).unwrap();
+        assert_eq!(created.module, src_dir.join("day_12.rs"));
+        assert_eq!(std::fs::read_to_string(&created.input).unwrap(), "");
+        assert_eq!(created.example, examples_dir.join("day_12_example.txt"));
+        assert_eq!(std::fs::read_to_string(&created.example).unwrap(), EXAMPLE);
+        assert_eq!(
+            std::fs::read_to_string(&answers_path).unwrap(),
+            "[day_01_example]\npart_1 = 24000\n\n[day_12_example]\npart_1 = 2\npart_2 = 2\n"
+        );
+        assert!(matches!(
+            create_day(12, &src_dir, &inputs_dir, &examples_dir),
+            Err(NewDayError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            create_day(26, &src_dir, &inputs_dir, &examples_dir),
+            Err(NewDayError::InvalidDay(26))
+        ));
+
+        // The answers are not added twice
+        std::fs::remove_file(&created.module).unwrap();
+        create_day(12, &src_dir, &inputs_dir, &examples_dir).unwrap();
+        let examples = crate::answers::load_examples(&examples_dir).unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[1].name, "day_12_example");
+        assert_eq!(examples[1].parts(), vec![1, 2]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
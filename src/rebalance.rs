@@ -0,0 +1,509 @@
+use crate::day_01::Calories;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::num::NonZeroUsize;
+
+/** Above this number of items, the exact solver is too slow and the heuristics are used. */
+pub const EXACT_MAX_ITEMS: usize = 16;
+
+/** An item of the day 1 inventories, and where it was before the rebalancing. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Item {
+    pub elf: usize,
+    /** Position of the item in the inventory of its elf */
+    pub index: usize,
+    pub calories: Calories,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    Exact,
+    Greedy,
+    KarmarkarKarp,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Method::Exact => write!(f, "exact"),
+            Method::Greedy => write!(f, "greedy"),
+            Method::KarmarkarKarp => write!(f, "Karmarkar-Karp"),
+        }
+    }
+}
+
+/** The items carried by every elf after the rebalancing. */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Plan {
+    pub method: Method,
+    /** Only the elves which can be given an item, at most one per item */
+    pub elves: Vec<Vec<Item>>,
+    /** The other elves, which carry nothing and are only counted */
+    pub empty_elves: usize,
+    /** The biggest total carried by an elf, which is minimised */
+    pub max_total: Calories,
+    /** The biggest total before the rebalancing */
+    pub previous_max_total: Calories,
+}
+
+pub fn items(data: &[Vec<Calories>]) -> Vec<Item> {
+    return data
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, inventory)| {
+            inventory
+                .iter()
+                .enumerate()
+                .map(move |(index, &calories)| Item {
+                    elf,
+                    index,
+                    calories,
+                })
+        })
+        .collect();
+}
+
+/**
+ * The items sorted from the biggest, as the solvers place them in this order.
+ * The parser checks that the calories of all the items add up, so the sums can't overflow.
+ */
+fn sorted_calories(items: &[Item]) -> Vec<(i64, usize)> {
+    let mut sorted = items
+        .iter()
+        .enumerate()
+        .map(|(position, item)| (item.calories.get(), position))
+        .collect::<Vec<(i64, usize)>>();
+    sorted.sort_by_key(|&(calories, position)| (Reverse(calories), position));
+    return sorted;
+}
+
+/** The elves of a plan, from the elf of every item. */
+fn to_elves(items: &[Item], elf_of_items: &[usize], elves: usize) -> Vec<Vec<Item>> {
+    let mut planned = vec![Vec::new(); elves];
+    for (item, &elf) in items.iter().zip(elf_of_items) {
+        planned[elf].push(*item);
+    }
+    return planned;
+}
+
+fn max_total(elves: &[Vec<Item>]) -> Calories {
+    return elves
+        .iter()
+        .map(|items| {
+            crate::day_01::total(&items.iter().map(|item| item.calories).collect::<Vec<_>>())
+        })
+        .max()
+        .unwrap_or_default();
+}
+
+/**
+ * The elves which can be given an item, as every item goes to a single elf.
+ * The solvers only use these, so a big number of elves doesn't cost memory.
+ */
+fn usable_elves(items: &[Item], elves: NonZeroUsize) -> usize {
+    return elves.get().min(items.len()).max(1);
+}
+
+/** Longest processing time first: every item goes to the elf carrying the least. */
+fn greedy_elf_of_items(items: &[Item], elves: NonZeroUsize) -> Vec<usize> {
+    let elves = usable_elves(items, elves);
+    let mut loads = (0..elves)
+        .map(|elf| Reverse((0, elf)))
+        .collect::<BinaryHeap<Reverse<(i64, usize)>>>();
+    let mut elf_of_items = vec![0; items.len()];
+    for (calories, position) in sorted_calories(items) {
+        let Reverse((load, elf)) = loads.pop().expect("There is at least one elf");
+        elf_of_items[position] = elf;
+        loads.push(Reverse((load + calories, elf)));
+    }
+    return elf_of_items;
+}
+
+/** The elves beyond the number of items would carry nothing, and are left out. */
+pub fn greedy(items: &[Item], elves: NonZeroUsize) -> Vec<Vec<Item>> {
+    let elf_of_items = greedy_elf_of_items(items, elves);
+    return to_elves(items, &elf_of_items, usable_elves(items, elves));
+}
+
+/** Neither the biggest item nor the mean can be split. */
+fn lower_bound(items: &[Item], elves: NonZeroUsize) -> i64 {
+    let sum = items.iter().map(|item| item.calories.get()).sum::<i64>();
+    let biggest = items.iter().map(|item| item.calories.get()).max();
+    let elves = usable_elves(items, elves) as i64;
+    // The rounded up mean, as the sum can be close to i64::MAX
+    return biggest
+        .unwrap_or(0)
+        .max(sum / elves + (sum % elves != 0) as i64);
+}
+
+/**
+ * A partial partition of the Karmarkar-Karp heuristic, its subsets sorted from the biggest.
+ * Only the subsets with items are kept, the others of the `elves` are empty.
+ */
+struct Partition {
+    subsets: Vec<(i64, Vec<usize>)>,
+}
+
+impl Partition {
+    fn difference(&self, elves: usize) -> i64 {
+        let smallest = match self.subsets.len() < elves {
+            true => 0,
+            false => self.subsets[elves - 1].0,
+        };
+        return self.subsets[0].0 - smallest;
+    }
+
+    /** The subset of the partition at this position, empty when it isn't kept. */
+    fn take(&mut self, position: usize) -> (i64, Vec<usize>) {
+        return self
+            .subsets
+            .get_mut(position)
+            .map(std::mem::take)
+            .unwrap_or_default();
+    }
+}
+
+/**
+ * Largest differencing method: starts with a partition per item, and repeatedly merges
+ * the two partitions with the largest differences, the biggest subsets of one
+ * with the smallest subsets of the other.
+ */
+fn karmarkar_karp_elf_of_items(items: &[Item], elves: NonZeroUsize) -> Vec<usize> {
+    let elves = usable_elves(items, elves);
+    let mut partitions = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for (calories, position) in sorted_calories(items) {
+        heap.push((calories, Reverse(partitions.len())));
+        partitions.push(Some(Partition {
+            subsets: vec![(calories, vec![position])],
+        }));
+    }
+
+    while heap.len() > 1 {
+        let (_, Reverse(first)) = heap.pop().unwrap();
+        let (_, Reverse(second)) = heap.pop().unwrap();
+        let mut first = partitions[first].take().unwrap();
+        let mut second = partitions[second].take().unwrap();
+
+        // The subset at a position of the first partition is merged with the subset
+        // at the mirrored position of the second one, only the merges with items are done
+        let kept = first.subsets.len();
+        let mirrored = elves - second.subsets.len();
+        let mut subsets = (0..kept)
+            .chain(mirrored.max(kept)..elves)
+            .map(|position| {
+                let (sum_a, mut items_a) = first.take(position);
+                let (sum_b, items_b) = second.take(elves - 1 - position);
+                items_a.extend(items_b);
+                return (sum_a + sum_b, items_a);
+            })
+            .collect::<Vec<(i64, Vec<usize>)>>();
+        subsets.sort_by_key(|(sum, _)| Reverse(*sum));
+
+        let merged = Partition { subsets };
+        heap.push((merged.difference(elves), Reverse(partitions.len())));
+        partitions.push(Some(merged));
+    }
+
+    let mut elf_of_items = vec![0; items.len()];
+    if let Some((_, Reverse(last))) = heap.pop() {
+        let last = partitions[last].take().unwrap();
+        for (elf, (_, positions)) in last.subsets.into_iter().enumerate() {
+            for position in positions {
+                elf_of_items[position] = elf;
+            }
+        }
+    }
+    return elf_of_items;
+}
+
+/** The elves beyond the number of items would carry nothing, and are left out. */
+pub fn karmarkar_karp(items: &[Item], elves: NonZeroUsize) -> Vec<Vec<Item>> {
+    let elf_of_items = karmarkar_karp_elf_of_items(items, elves);
+    return to_elves(items, &elf_of_items, usable_elves(items, elves));
+}
+
+/** Branch and bound over the items sorted from the biggest, starting from the best heuristic. */
+struct Search {
+    sorted: Vec<(i64, usize)>,
+    /** Calories of the items from the next one to place */
+    remaining: Vec<i64>,
+    loads: Vec<i64>,
+    elf_of_items: Vec<usize>,
+    best: i64,
+    best_elf_of_items: Vec<usize>,
+    /** No plan can do better than this */
+    lower_bound: i64,
+}
+
+impl Search {
+    fn place(&mut self, next: usize) {
+        if self.best == self.lower_bound {
+            return;
+        }
+        let Some(&(calories, position)) = self.sorted.get(next) else {
+            let max_load = self.loads.iter().copied().max().unwrap_or_default();
+            if max_load < self.best {
+                self.best = max_load;
+                self.best_elf_of_items = self.elf_of_items.clone();
+            }
+            return;
+        };
+
+        // A better plan needs enough room left for all the remaining items.
+        // The room of all the elves can add up to more than i64::MAX
+        let room = self
+            .loads
+            .iter()
+            .map(|load| (self.best - 1 - load).max(0))
+            .fold(0, i64::saturating_add);
+        if self.remaining[next] > room {
+            return;
+        }
+
+        // The least loaded elves first, as they lead to the good plans sooner.
+        // The elves carrying the same load are interchangeable
+        let mut elves = (0..self.loads.len()).collect::<Vec<usize>>();
+        elves.sort_by_key(|&elf| (self.loads[elf], elf));
+        elves.dedup_by_key(|elf| self.loads[*elf]);
+        for elf in elves {
+            // Only a better plan is interesting
+            if self.loads[elf] + calories >= self.best {
+                break;
+            }
+            self.loads[elf] += calories;
+            self.elf_of_items[position] = elf;
+            self.place(next + 1);
+            self.loads[elf] -= calories;
+        }
+    }
+}
+
+/**
+ * The optimal plan, only usable with a few items as it can take an exponential time.
+ * The elves beyond the number of items would carry nothing, and are left out.
+ */
+pub fn exact(items: &[Item], elves: NonZeroUsize) -> Vec<Vec<Item>> {
+    let usable = usable_elves(items, elves);
+    let start = [
+        greedy_elf_of_items(items, elves),
+        karmarkar_karp_elf_of_items(items, elves),
+    ]
+    .into_iter()
+    .min_by_key(|elf_of_items| max_total(&to_elves(items, elf_of_items, usable)))
+    .unwrap();
+
+    let sorted = sorted_calories(items);
+    let mut remaining = sorted
+        .iter()
+        .rev()
+        .scan(0, |sum, (calories, _)| {
+            *sum += calories;
+            return Some(*sum);
+        })
+        .collect::<Vec<i64>>();
+    remaining.reverse();
+    remaining.push(0);
+
+    let mut search = Search {
+        sorted,
+        remaining,
+        loads: vec![0; usable],
+        elf_of_items: vec![0; items.len()],
+        best: max_total(&to_elves(items, &start, usable)).get(),
+        best_elf_of_items: start,
+        lower_bound: lower_bound(items, elves),
+    };
+    search.place(0);
+    return to_elves(items, &search.best_elf_of_items, usable);
+}
+
+/**
+ * Moves the items between `elves` elves so that the biggest total carried by an elf
+ * is as small as possible. The plan is exact with few items, and otherwise
+ * the best of the greedy and Karmarkar-Karp heuristics.
+ */
+pub fn rebalance(data: &[Vec<Calories>], elves: NonZeroUsize) -> Plan {
+    let items = items(data);
+    let previous_max_total = data
+        .iter()
+        .map(|elf| crate::day_01::total(elf))
+        .max()
+        .unwrap_or_default();
+    if items.len() <= EXACT_MAX_ITEMS {
+        let planned = exact(&items, elves);
+        return Plan {
+            method: Method::Exact,
+            max_total: max_total(&planned),
+            previous_max_total,
+            empty_elves: elves.get() - planned.len(),
+            elves: planned,
+        };
+    }
+
+    let greedy = greedy(&items, elves);
+    let karmarkar_karp = karmarkar_karp(&items, elves);
+    let (method, planned) = match max_total(&greedy) < max_total(&karmarkar_karp) {
+        true => (Method::Greedy, greedy),
+        false => (Method::KarmarkarKarp, karmarkar_karp),
+    };
+    return Plan {
+        method,
+        max_total: max_total(&planned),
+        previous_max_total,
+        empty_elves: elves.get() - planned.len(),
+        elves: planned,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_01::parse_input_data;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("../inputs/examples/day_01_example.txt");
+
+    fn non_zero(elves: usize) -> NonZeroUsize {
+        return NonZeroUsize::new(elves).unwrap();
+    }
+
+    fn calories(elves: &[Vec<i64>]) -> Vec<Vec<Calories>> {
+        return elves
+            .iter()
+            .map(|elf| elf.iter().map(|c| Calories::new(*c).unwrap()).collect())
+            .collect();
+    }
+
+    /** Every item is given to exactly one elf */
+    fn is_assignment(data: &[Vec<Calories>], planned: &[Vec<Item>]) -> bool {
+        let mut planned_items = planned.iter().flatten().copied().collect::<Vec<Item>>();
+        planned_items.sort_by_key(|item| (item.elf, item.index));
+        return planned_items == items(data);
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let data = parse_input_data(EXAMPLE).unwrap();
+        let plan = rebalance(&data, non_zero(data.len()));
+        assert_eq!(plan.method, Method::Exact);
+        assert!(is_assignment(&data, &plan.elves));
+        // 55000 calories in 10 items, the biggest is 10000
+        assert_eq!(plan.max_total.get(), 11000);
+        assert_eq!(plan.previous_max_total.get(), 24000);
+
+        let plan = rebalance(&data, non_zero(2));
+        assert_eq!(plan.max_total.get(), 28000);
+        let plan = rebalance(&data, non_zero(20));
+        assert_eq!(plan.max_total.get(), 10000);
+        // One elf per item, the others carry nothing
+        assert_eq!((plan.elves.len(), plan.empty_elves), (10, 10));
+    }
+
+    #[test]
+    fn test_heuristics() {
+        // The greedy plan is 3 + 2 + 2, 3 + 2 when 3 + 3, 2 + 2 + 2 is possible
+        let data = calories(&[vec![3, 3], vec![2, 2, 2]]);
+        let few_items = items(&data);
+        assert_eq!(max_total(&greedy(&few_items, non_zero(2))).get(), 7);
+        assert_eq!(max_total(&exact(&few_items, non_zero(2))).get(), 6);
+
+        // Karmarkar-Karp is better than greedy here, but not optimal: 8 + 7, 6 + 5 + 4 is
+        let data = calories(&[vec![4, 5, 6, 7, 8]]);
+        let few_items = items(&data);
+        assert_eq!(max_total(&greedy(&few_items, non_zero(2))).get(), 17);
+        assert_eq!(
+            max_total(&karmarkar_karp(&few_items, non_zero(2))).get(),
+            16
+        );
+        assert_eq!(max_total(&exact(&few_items, non_zero(2))).get(), 15);
+
+        let data = parse_input_data(&crate::generate::generate(1, 200, 0).unwrap()).unwrap();
+        let plan = rebalance(&data, non_zero(data.len()));
+        assert_ne!(plan.method, Method::Exact);
+        assert!(is_assignment(&data, &plan.elves));
+    }
+
+    #[test]
+    fn test_big_totals_and_many_elves() {
+        let data = calories(&[vec![i64::MAX]]);
+        assert_eq!(rebalance(&data, non_zero(2)).max_total.get(), i64::MAX);
+        let data = calories(&[vec![i64::MAX - 2, 1], vec![1]]);
+        let plan = rebalance(&data, non_zero(2));
+        assert_eq!(plan.max_total.get(), i64::MAX - 2);
+        assert_eq!(lower_bound(&items(&data), non_zero(2)), i64::MAX - 2);
+
+        // Only as many elves as items are used by the solvers
+        let data = parse_input_data(&crate::generate::generate(1, 200, 0).unwrap()).unwrap();
+        let many_items = items(&data);
+        let plan = rebalance(&data, non_zero(100_000));
+        assert_eq!(plan.elves.len(), many_items.len());
+        assert_eq!(plan.empty_elves, 100_000 - many_items.len());
+        // Far more elves than could be allocated
+        let plan = rebalance(&data, non_zero(100_000_000_000));
+        assert_eq!(plan.empty_elves, 100_000_000_000 - many_items.len());
+        assert_eq!(
+            plan.max_total.get(),
+            many_items
+                .iter()
+                .map(|item| item.calories.get())
+                .max()
+                .unwrap()
+        );
+    }
+
+    /** Tries every assignment, to check the exact solver */
+    fn brute_force(items: &[Item], elves: usize) -> i64 {
+        let mut best = i64::MAX;
+        for assignment in 0..elves.pow(items.len() as u32) {
+            let mut loads = vec![0; elves];
+            let mut rest = assignment;
+            for item in items {
+                loads[rest % elves] += item.calories.get();
+                rest /= elves;
+            }
+            best = best.min(loads.into_iter().max().unwrap());
+        }
+        return best;
+    }
+
+    fn inventories(max_items: usize) -> impl Strategy<Value = Vec<Vec<Calories>>> {
+        let calories = (0..1000i64).prop_map(|c| Calories::new(c).unwrap());
+        return prop::collection::vec(prop::collection::vec(calories, 0..4), 1..max_items);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_exact_is_optimal(data in inventories(4), elves in 1..4usize) {
+            let elves = non_zero(elves);
+            let items = items(&data);
+            let planned = exact(&items, elves);
+            prop_assert!(is_assignment(&data, &planned));
+            prop_assert_eq!(max_total(&planned).get(), brute_force(&items, elves.get()));
+        }
+
+        #[test]
+        fn prop_heuristics_are_assignments(data in inventories(30), elves in 1..10usize) {
+            let elves = non_zero(elves);
+            let items = items(&data);
+            for planned in [greedy(&items, elves), karmarkar_karp(&items, elves)] {
+                prop_assert!(is_assignment(&data, &planned));
+                prop_assert_eq!(planned.len(), elves.get().min(items.len()).max(1));
+                prop_assert!(max_total(&planned).get() >= lower_bound(&items, elves));
+            }
+        }
+
+        #[test]
+        fn prop_exact_is_the_best(data in inventories(7), elves in 1..6usize) {
+            let elves = non_zero(elves);
+            let items = items(&data);
+            let exact_max = max_total(&exact(&items, elves));
+            prop_assert!(exact_max.get() >= lower_bound(&items, elves));
+            prop_assert!(exact_max <= max_total(&greedy(&items, elves)));
+            prop_assert!(exact_max <= max_total(&karmarkar_karp(&items, elves)));
+        }
+    }
+}
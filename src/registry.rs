@@ -0,0 +1,155 @@
+use crate::error::{Error, ParseError};
+use crate::runner::{self, DayRun};
+use crate::solution::{Solution, StreamingSolution};
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+pub type StreamFn = fn(&mut dyn BufRead, &[u8]) -> Result<DayRun, Error>;
+
+/**
+ * A solved day, with the function parsing its input and running its parts.
+ * Every day module gives its own from a `register` function, called by the registry.
+ */
+pub struct Day {
+    pub day: u8,
+    pub run: fn(&str, &[u8], usize) -> Result<DayRun, ParseError>,
+    /** Only for the days that can be solved while reading their input */
+    pub stream: Option<StreamFn>,
+}
+
+impl Day {
+    pub fn new<S: Solution>() -> Day {
+        Day {
+            day: S::DAY,
+            run: runner::run_day::<S>,
+            stream: None,
+        }
+    }
+
+    pub fn streaming<S: StreamingSolution>() -> Day {
+        Day {
+            stream: Some(runner::run_stream::<S>),
+            ..Day::new::<S>()
+        }
+    }
+}
+
+/** The days of every `src/day_NN.rs` module, sorted by day, as found by build.rs. */
+pub fn days() -> Vec<Day> {
+    return include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+}
+
+/** The registered day with this number, if it is implemented. */
+pub fn find(day: u8) -> Option<Day> {
+    return days().into_iter().find(|registered| registered.day == day);
+}
+
+/**
+ * The registered days in the range, or every day without a range.
+ * Fails with every day of the range that isn't implemented, so none is run.
+ */
+pub fn select(range: Option<&RangeInclusive<u8>>) -> Result<Vec<Day>, Vec<Error>> {
+    let days = days();
+    let Some(range) = range else {
+        return Ok(days);
+    };
+    let missing = range
+        .clone()
+        .filter(|number| !days.iter().any(|day| day.day == *number))
+        .map(Error::NotImplemented)
+        .collect::<Vec<Error>>();
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+    return Ok(days
+        .into_iter()
+        .filter(|day| range.contains(&day.day))
+        .collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+
+    #[test]
+    fn test_days_are_sorted_and_unique() {
+        let days = days();
+        for pair in days.windows(2) {
+            assert!(pair[0].day < pair[1].day);
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select(None).unwrap().len(), days().len());
+        let selected = select(Some(&(3..=5))).unwrap();
+        assert_eq!(
+            selected.iter().map(|day| day.day).collect::<Vec<u8>>(),
+            vec![3, 4, 5]
+        );
+
+        let Err(missing) = select(Some(&(8..=11))) else {
+            panic!("Days 10 and 11 are not implemented");
+        };
+        assert_eq!(
+            missing
+                .iter()
+                .map(Error::to_string)
+                .collect::<Vec<String>>(),
+            vec!["Day 10 is not implemented", "Day 11 is not implemented"]
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let days = days();
+        let day_4 = days.iter().find(|day| day.day == 4).unwrap();
+        let run = (day_4.run)("2-4,6-8\n2-8,3-7", &[2, 1], 1).unwrap();
+        assert_eq!(run.day, 4);
+        assert_eq!(run.parts.len(), 2);
+        assert_eq!(run.parts[0].part, 2);
+        assert_eq!(run.parts[0].answer, Answer::Integer(1));
+        assert_eq!(run.parts[1].answer, Answer::Integer(1));
+
+        assert!((day_4.run)("2-4", &[1], 1).is_err());
+    }
+
+    #[test]
+    fn test_stream() {
+        let days = days();
+        let day_4 = days.iter().find(|day| day.day == 4).unwrap();
+        let stream = day_4.stream.unwrap();
+        let run = stream(&mut "2-4,6-8\n2-8,3-7\n".as_bytes(), &[2]).unwrap();
+        assert_eq!(run.parts.len(), 1);
+        assert_eq!(run.parts[0].answer, Answer::Integer(1));
+
+        assert!(stream(&mut "2-4".as_bytes(), &[1]).is_err());
+
+        let day_5 = days.iter().find(|day| day.day == 5).unwrap();
+        assert!(day_5.stream.is_none());
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/examples");
+        let examples = crate::answers::load_examples(&examples_dir).unwrap();
+        assert!(!examples.is_empty());
+
+        let days = days();
+        for example in examples {
+            let day = days.iter().find(|day| day.day == example.day).unwrap();
+            let input = std::fs::read_to_string(&example.path).unwrap();
+            let run = (day.run)(&input, &example.parts(), 1).unwrap();
+            for part in run.parts {
+                assert_eq!(
+                    Some(&part.answer),
+                    example.get(part.part),
+                    "{}, part {}",
+                    example.name,
+                    part.part
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use crate::answers::Verification;
+use crate::day_01::{self, Statistics};
+use crate::rebalance::Plan;
+use crate::runner::DayRun;
+use crate::solution::Answer;
+use serde::Serialize;
+use std::io::Write;
+use std::time::Duration;
+
+/**
+ * One answered part, as written by the JSON and CSV formats.
+ * Durations are in nanoseconds, and the parsing durations are repeated
+ * on both parts of a day.
+ */
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub answer_type: &'static str,
+    pub runs: usize,
+    pub parse_median_ns: u128,
+    pub parse_min_ns: u128,
+    pub median_ns: u128,
+    pub min_ns: u128,
+}
+
+const CSV_HEADER: &str =
+    "day,part,answer,answer_type,runs,parse_median_ns,parse_min_ns,median_ns,min_ns";
+
+pub fn records(runs: &[DayRun]) -> Vec<Record> {
+    return runs
+        .iter()
+        .flat_map(|run| {
+            run.parts.iter().map(|part| Record {
+                day: run.day,
+                part: part.part,
+                answer: part.answer.clone(),
+                answer_type: part.answer.answer_type(),
+                runs: part.timing.samples.len(),
+                parse_median_ns: run.parse.median().as_nanos(),
+                parse_min_ns: run.parse.min().as_nanos(),
+                median_ns: part.timing.median().as_nanos(),
+                min_ns: part.timing.min().as_nanos(),
+            })
+        })
+        .collect();
+}
+
+pub fn write_json(runs: &[DayRun], mut writer: impl Write) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, &records(runs))?;
+    return writeln!(writer);
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return field.to_string();
+}
+
+pub fn write_csv(runs: &[DayRun], mut writer: impl Write) -> std::io::Result<()> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+    for record in records(runs) {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            escape_csv(&record.answer.to_string()),
+            record.answer_type,
+            record.runs,
+            record.parse_median_ns,
+            record.parse_min_ns,
+            record.median_ns,
+            record.min_ns
+        )?;
+    }
+    return Ok(());
+}
+
+pub fn print_answers(run: &DayRun) {
+    println!("Day {}, parse: {:?}", run.day, run.parse.median());
+    for part in run.parts.iter() {
+        println!("Day {}, part {}: {}", run.day, part.part, part.answer);
+    }
+}
+
+/** Prints the verdict of every part, or the error preventing them. */
+pub fn print_verification(verification: &Verification) {
+    match &verification.parts {
+        Ok(parts) => {
+            for (part, verdict) in parts {
+                println!("{}, part {}: {}", verification.name, part, verdict);
+            }
+        }
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+/** Writes the median and minimum duration of every phase, and their totals. */
+pub fn write_timings(runs: &[DayRun], mut writer: impl Write) -> std::io::Result<()> {
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{:>3}  {:<6} {:>12} {:>12}",
+        "Day", "Phase", "Median", "Min"
+    )?;
+
+    let mut total_median = Duration::ZERO;
+    let mut total_min = Duration::ZERO;
+    for run in runs {
+        let phases = std::iter::once(("parse".to_string(), &run.parse)).chain(
+            run.parts
+                .iter()
+                .map(|part| (format!("part {}", part.part), &part.timing)),
+        );
+        for (phase, timing) in phases {
+            writeln!(
+                writer,
+                "{:>3}  {:<6} {:>12.3?} {:>12.3?}",
+                run.day,
+                phase,
+                timing.median(),
+                timing.min()
+            )?;
+            total_median += timing.median();
+            total_min += timing.min();
+        }
+    }
+
+    return writeln!(
+        writer,
+        "{:<11} {:>12.3?} {:>12.3?}",
+        "Total", total_median, total_min
+    );
+}
+
+fn list_elves(elves: &[usize]) -> String {
+    if elves.is_empty() {
+        return "none".to_string();
+    }
+    return elves
+        .iter()
+        .map(|elf| elf.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+/** Human readable statistics of the day 1 inventories, the elves are numbered from 0. */
+pub fn write_statistics(statistics: &Statistics, mut writer: impl Write) -> std::io::Result<()> {
+    writeln!(writer, "{:>5} {:>10} {:>6}", "Elf", "Calories", "Items")?;
+    for elf in &statistics.elves {
+        writeln!(writer, "{:>5} {:>10} {:>6}", elf.elf, elf.total, elf.items)?;
+    }
+    writeln!(writer)?;
+
+    if let Some(summary) = &statistics.summary {
+        writeln!(writer, "Min: {}, max: {}", summary.min, summary.max)?;
+        writeln!(
+            writer,
+            "Mean: {:.1}, median: {:.1}",
+            summary.mean, summary.median
+        )?;
+        let percentiles = summary
+            .percentiles
+            .iter()
+            .map(|p| format!("{}th: {}", p.percentile, p.total))
+            .collect::<Vec<String>>();
+        writeln!(writer, "Percentiles: {}", percentiles.join(", "))?;
+
+        writeln!(writer, "Histogram:")?;
+        let biggest = summary.histogram.iter().map(|b| b.count).max().unwrap_or(1);
+        for bucket in &summary.histogram {
+            // The biggest bucket is 40 characters wide
+            let bar = "#".repeat((bucket.count * 40).div_ceil(biggest));
+            writeln!(
+                writer,
+                "{:>10} - {:>10} | {:<40} {}",
+                bucket.start, bucket.end, bar, bucket.count
+            )?;
+        }
+    }
+
+    writeln!(
+        writer,
+        "Empty inventories: {}",
+        list_elves(&statistics.empty)
+    )?;
+    let duplicates = match statistics.duplicates.is_empty() {
+        true => "none".to_string(),
+        false => statistics
+            .duplicates
+            .iter()
+            .map(|elves| list_elves(elves))
+            .collect::<Vec<String>>()
+            .join("; "),
+    };
+    return writeln!(writer, "Duplicate inventories: {}", duplicates);
+}
+
+pub fn write_statistics_json(
+    statistics: &Statistics,
+    mut writer: impl Write,
+) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, statistics)?;
+    return writeln!(writer);
+}
+
+/** Only the statistics of every elf, the rest is computed from them. */
+pub fn write_statistics_csv(
+    statistics: &Statistics,
+    mut writer: impl Write,
+) -> std::io::Result<()> {
+    writeln!(writer, "elf,total,items")?;
+    for elf in &statistics.elves {
+        writeln!(writer, "{},{},{}", elf.elf, elf.total, elf.items)?;
+    }
+    return Ok(());
+}
+
+/** Human readable plan, with where every item comes from. */
+pub fn write_plan(plan: &Plan, mut writer: impl Write) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "Method: {}, biggest total: {} (was {})",
+        plan.method, plan.max_total, plan.previous_max_total
+    )?;
+    writeln!(writer, "{:>5} {:>10}  Items (from elf)", "Elf", "Calories")?;
+    for (elf, items) in plan.elves.iter().enumerate() {
+        let calories = items.iter().map(|item| item.calories).collect::<Vec<_>>();
+        let items = items
+            .iter()
+            .map(|item| format!("{} ({})", item.calories, item.elf))
+            .collect::<Vec<String>>();
+        writeln!(
+            writer,
+            "{:>5} {:>10}  {}",
+            elf,
+            day_01::total(&calories),
+            items.join(", ")
+        )?;
+    }
+    let first_empty = plan.elves.len();
+    match plan.empty_elves {
+        0 => {}
+        1 => writeln!(writer, "Elf {} carries nothing", first_empty)?,
+        empty_elves => writeln!(
+            writer,
+            "Elves {} to {} carry nothing",
+            first_empty,
+            first_empty + empty_elves - 1
+        )?,
+    }
+    return Ok(());
+}
+
+pub fn write_plan_json(plan: &Plan, mut writer: impl Write) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, plan)?;
+    return writeln!(writer);
+}
+
+/** One line per item, with the elf carrying it after and before the rebalancing. */
+pub fn write_plan_csv(plan: &Plan, mut writer: impl Write) -> std::io::Result<()> {
+    writeln!(writer, "elf,calories,previous_elf,previous_index")?;
+    for (elf, items) in plan.elves.iter().enumerate() {
+        for item in items {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                elf, item.calories, item.elf, item.index
+            )?;
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_01::{statistics, Calories};
+    use crate::runner::{PartRun, Timing};
+    use std::num::NonZeroUsize;
+
+    fn runs() -> Vec<DayRun> {
+        let timing = |nanoseconds: &[u64]| Timing {
+            samples: nanoseconds
+                .iter()
+                .map(|ns| Duration::from_nanos(*ns))
+                .collect(),
+        };
+        return vec![DayRun {
+            day: 5,
+            parse: timing(&[300, 100]),
+            parts: vec![
+                PartRun {
+                    part: 1,
+                    answer: Answer::Text("CMZ".to_string()),
+                    timing: timing(&[40, 20]),
+                },
+                PartRun {
+                    part: 2,
+                    answer: Answer::Integer(42),
+                    timing: timing(&[7, 5]),
+                },
+            ],
+        }];
+    }
+
+    // Other tools consume these formats, change them with care
+    #[test]
+    fn test_json_schema() {
+        let mut output = Vec::new();
+        write_json(&runs(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"[
+  {
+    "day": 5,
+    "part": 1,
+    "answer": "CMZ",
+    "answer_type": "text",
+    "runs": 2,
+    "parse_median_ns": 200,
+    "parse_min_ns": 100,
+    "median_ns": 30,
+    "min_ns": 20
+  },
+  {
+    "day": 5,
+    "part": 2,
+    "answer": 42,
+    "answer_type": "integer",
+    "runs": 2,
+    "parse_median_ns": 200,
+    "parse_min_ns": 100,
+    "median_ns": 6,
+    "min_ns": 5
+  }
+]
+"#
+        );
+    }
+
+    #[test]
+    fn test_csv_schema() {
+        let mut output = Vec::new();
+        write_csv(&runs(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "day,part,answer,answer_type,runs,parse_median_ns,parse_min_ns,median_ns,min_ns
+5,1,CMZ,text,2,200,100,30,20
+5,2,42,integer,2,200,100,6,5
+"
+        );
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("CMZ"), "CMZ");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_write_statistics() {
+        let calories = |items: &[i64]| -> Vec<Calories> {
+            return items.iter().map(|c| Calories::new(*c).unwrap()).collect();
+        };
+        let elves = [calories(&[1000, 2000]), vec![], calories(&[3000]), vec![]];
+        let statistics = statistics(&elves, 2);
+        let mut output = Vec::new();
+        write_statistics(&statistics, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "  Elf   Calories  Items
+    0       3000      2
+    1          0      0
+    2       3000      1
+    3          0      0
+
+Min: 0, max: 3000
+Mean: 1500.0, median: 1500.0
+Percentiles: 10th: 0, 25th: 0, 75th: 3000, 90th: 3000, 99th: 3000
+Histogram:
+         0 -       1500 | ######################################## 2
+      1501 -       3000 | ######################################## 2
+Empty inventories: 1, 3
+Duplicate inventories: 1, 3
+"
+        );
+
+        let mut output = Vec::new();
+        write_statistics_csv(&statistics, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "elf,total,items\n0,3000,2\n1,0,0\n2,3000,1\n3,0,0\n"
+        );
+    }
+
+    #[test]
+    fn test_write_plan() {
+        let elves = [1000, 2000, 3000]
+            .iter()
+            .map(|c| vec![Calories::new(*c).unwrap()])
+            .collect::<Vec<_>>();
+        let plan = crate::rebalance::rebalance(&elves, NonZeroUsize::new(2).unwrap());
+        let mut output = Vec::new();
+        write_plan(&plan, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Method: exact, biggest total: 3000 (was 3000)
+  Elf   Calories  Items (from elf)
+    0       3000  3000 (2)
+    1       3000  1000 (0), 2000 (1)
+"
+        );
+
+        let mut output = Vec::new();
+        write_plan_csv(&plan, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "elf,calories,previous_elf,previous_index\n0,3000,2,0\n1,1000,0,0\n1,2000,1,0\n"
+        );
+        let plan = crate::rebalance::rebalance(&elves, NonZeroUsize::new(5).unwrap());
+        let mut output = Vec::new();
+        write_plan(&plan, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("    2       1000  1000 (0)\nElves 3 to 4 carry nothing\n"));
+        let plan = crate::rebalance::rebalance(&elves, NonZeroUsize::new(4).unwrap());
+        let mut output = Vec::new();
+        write_plan(&plan, &mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("Elf 3 carries nothing\n"));
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt;
+
+/** A move of a game, by its position in the rules. */
+pub type Move = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Win,
+    Lose,
+    Draw,
+}
+
+/** Points of a game given by its result, added to the points of the played move. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scoring {
+    pub win: i64,
+    pub draw: i64,
+    pub lose: i64,
+}
+
+/** 6 points for a win, 3 for a draw and none for a loss, like in the puzzle. */
+pub const PUZZLE_SCORING: Scoring = Scoring {
+    win: 6,
+    draw: 3,
+    lose: 0,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    NoMoves,
+    UnknownMove(String),
+    DuplicateMove(String),
+    /** A move that beats itself */
+    SelfBeating(String),
+    /** Two moves beating each other */
+    MutualBeating(String, String),
+    /** Renaming the moves with another number of names than of moves */
+    WrongNumberOfNames {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::NoMoves => write!(f, "A game needs at least one move"),
+            RulesError::UnknownMove(name) => write!(f, "The move {} is not in the game", name),
+            RulesError::DuplicateMove(name) => write!(f, "The move {} is there twice", name),
+            RulesError::SelfBeating(name) => write!(f, "The move {} beats itself", name),
+            RulesError::MutualBeating(a, b) => {
+                write!(f, "The moves {} and {} beat each other", a, b)
+            }
+            RulesError::WrongNumberOfNames { expected, found } => {
+                write!(
+                    f,
+                    "Expected {} names for the moves, found {}",
+                    expected, found
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+/**
+ * A game like rock paper scissors, defined by a dominance graph between its moves,
+ * the points of every move and the points of the results.
+ * Two different moves without an edge between them make a draw.
+ */
+#[derive(Debug, Clone)]
+pub struct Rules {
+    names: Vec<String>,
+    move_points: Vec<i64>,
+    /** `beats[a][b]` when the move a beats the move b */
+    beats: Vec<Vec<bool>>,
+    scoring: Scoring,
+}
+
+impl Rules {
+    /** `moves` are the names and points of the moves, and `beats` the (winner, loser) edges. */
+    pub fn new(
+        moves: &[(&str, i64)],
+        beats: &[(&str, &str)],
+        scoring: Scoring,
+    ) -> Result<Rules, RulesError> {
+        if moves.is_empty() {
+            return Err(RulesError::NoMoves);
+        }
+        let names = moves
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<String>>();
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                return Err(RulesError::DuplicateMove(name.clone()));
+            }
+        }
+
+        let find = |name: &str| {
+            names
+                .iter()
+                .position(|known| known == name)
+                .ok_or_else(|| RulesError::UnknownMove(name.to_string()))
+        };
+        let mut matrix = vec![vec![false; names.len()]; names.len()];
+        for &(winner, loser) in beats {
+            let (winner, loser) = (find(winner)?, find(loser)?);
+            if winner == loser {
+                return Err(RulesError::SelfBeating(names[winner].clone()));
+            }
+            if matrix[loser][winner] {
+                return Err(RulesError::MutualBeating(
+                    names[winner].clone(),
+                    names[loser].clone(),
+                ));
+            }
+            matrix[winner][loser] = true;
+        }
+
+        return Ok(Rules {
+            move_points: moves.iter().map(|(_, points)| *points).collect(),
+            names,
+            beats: matrix,
+            scoring,
+        });
+    }
+
+    /** Rock, paper and scissors, worth 1, 2 and 3 points, as in the puzzle. */
+    pub fn classic() -> Rules {
+        return Rules::cyclic(3, PUZZLE_SCORING)
+            .and_then(|rules| rules.renamed(&["Rock", "Paper", "Scissors"]))
+            .unwrap();
+    }
+
+    /** Rock, paper, scissors, lizard and Spock, worth 1 to 5 points. */
+    pub fn rock_paper_scissors_lizard_spock() -> Rules {
+        let moves = [
+            ("Rock", 1),
+            ("Paper", 2),
+            ("Scissors", 3),
+            ("Lizard", 4),
+            ("Spock", 5),
+        ];
+        let beats = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        return Rules::new(&moves, &beats, PUZZLE_SCORING).unwrap();
+    }
+
+    /**
+     * `size` moves named 1 to `size` and worth as many points, where every move beats
+     * the half of the moves before it, wrapping around. With an odd size every move
+     * beats as many moves as it loses to, with an even size the opposite moves draw.
+     * A size of 0 gives `RulesError::NoMoves`.
+     */
+    pub fn cyclic(size: usize, scoring: Scoring) -> Result<Rules, RulesError> {
+        let names = (1..=size).map(|n| n.to_string()).collect::<Vec<String>>();
+        let moves = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index as i64 + 1))
+            .collect::<Vec<(&str, i64)>>();
+        let beats = (0..size)
+            .flat_map(|winner| {
+                (1..=size.saturating_sub(1) / 2)
+                    .map(move |distance| (winner, (winner + size - distance) % size))
+            })
+            .map(|(winner, loser)| (names[winner].as_str(), names[loser].as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        return Rules::new(&moves, &beats, scoring);
+    }
+
+    /** The same game with other names for the moves, in the same order. */
+    pub fn renamed(mut self, names: &[&str]) -> Result<Rules, RulesError> {
+        if names.len() != self.names.len() {
+            return Err(RulesError::WrongNumberOfNames {
+                expected: self.names.len(),
+                found: names.len(),
+            });
+        }
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                return Err(RulesError::DuplicateMove(name.to_string()));
+            }
+        }
+        self.names = names.iter().map(|name| name.to_string()).collect();
+        return Ok(self);
+    }
+
+    pub fn moves(&self) -> std::ops::Range<Move> {
+        return 0..self.names.len();
+    }
+
+    pub fn name(&self, played: Move) -> &str {
+        return &self.names[played];
+    }
+
+    pub fn find(&self, name: &str) -> Option<Move> {
+        return self.names.iter().position(|known| known == name);
+    }
+
+    pub fn result(&self, played: Move, opponent: Move) -> GameResult {
+        if self.beats[played][opponent] {
+            return GameResult::Win;
+        }
+        if self.beats[opponent][played] {
+            return GameResult::Lose;
+        }
+        return GameResult::Draw;
+    }
+
+    pub fn move_points(&self, played: Move) -> i64 {
+        return self.move_points[played];
+    }
+
+    pub fn result_points(&self, result: GameResult) -> i64 {
+        return match result {
+            GameResult::Win => self.scoring.win,
+            GameResult::Draw => self.scoring.draw,
+            GameResult::Lose => self.scoring.lose,
+        };
+    }
+
+    /** Points of the played move, and of the result of the game. */
+    pub fn score(&self, played: Move, opponent: Move) -> i64 {
+        return self.move_points(played) + self.result_points(self.result(played, opponent));
+    }
+
+    /**
+     * The move giving this result against the opponent move. When several moves do,
+     * the one worth the most points, and the first one of them.
+     */
+    pub fn move_for(&self, result: GameResult, opponent: Move) -> Option<Move> {
+        return self
+            .moves()
+            .filter(|&played| self.result(played, opponent) == result)
+            .min_by_key(|&played| (-self.move_points(played), played));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_classic() {
+        let rules = Rules::classic();
+        let [rock, paper, scissors] = ["Rock", "Paper", "Scissors"].map(|n| rules.find(n).unwrap());
+        assert_eq!(rules.result(rock, scissors), GameResult::Win);
+        assert_eq!(rules.result(rock, paper), GameResult::Lose);
+        assert_eq!(rules.result(paper, paper), GameResult::Draw);
+        assert_eq!(rules.result(scissors, paper), GameResult::Win);
+        assert_eq!(rules.score(paper, rock), 8);
+        assert_eq!(rules.score(rock, paper), 1);
+        assert_eq!(rules.score(scissors, scissors), 6);
+        assert_eq!(rules.move_for(GameResult::Win, scissors), Some(rock));
+        assert_eq!(rules.move_for(GameResult::Lose, rock), Some(scissors));
+        assert_eq!(rules.name(paper), "Paper");
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let spock = rules.find("Spock").unwrap();
+        let lizard = rules.find("Lizard").unwrap();
+        assert_eq!(rules.result(lizard, spock), GameResult::Win);
+        // Both paper and lizard beat Spock, the lizard is worth more
+        assert_eq!(rules.move_for(GameResult::Win, spock), Some(lizard));
+    }
+
+    #[test]
+    fn test_cyclic() {
+        for size in 1..10 {
+            let rules = Rules::cyclic(size, PUZZLE_SCORING).unwrap();
+            for played in rules.moves() {
+                let count = |result| {
+                    rules
+                        .moves()
+                        .filter(|&opponent| rules.result(played, opponent) == result)
+                        .count()
+                };
+                assert_eq!(count(GameResult::Win), (size - 1) / 2);
+                assert_eq!(count(GameResult::Lose), (size - 1) / 2);
+                assert_eq!(count(GameResult::Draw), 2 - size % 2);
+                for result in [GameResult::Win, GameResult::Lose, GameResult::Draw] {
+                    for opponent in rules.moves() {
+                        if let Some(played) = rules.move_for(result, opponent) {
+                            assert_eq!(rules.result(played, opponent), result);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let moves = [("Rock", 1), ("Paper", 2)];
+        assert_eq!(
+            Rules::new(&[], &[], PUZZLE_SCORING).unwrap_err(),
+            RulesError::NoMoves
+        );
+        assert_eq!(
+            Rules::new(&moves, &[("Rock", "Well")], PUZZLE_SCORING).unwrap_err(),
+            RulesError::UnknownMove("Well".to_string())
+        );
+        assert_eq!(
+            Rules::new(&[("Rock", 1), ("Rock", 2)], &[], PUZZLE_SCORING).unwrap_err(),
+            RulesError::DuplicateMove("Rock".to_string())
+        );
+        assert_eq!(
+            Rules::new(&moves, &[("Rock", "Rock")], PUZZLE_SCORING).unwrap_err(),
+            RulesError::SelfBeating("Rock".to_string())
+        );
+        assert_eq!(
+            Rules::new(
+                &moves,
+                &[("Paper", "Rock"), ("Rock", "Paper")],
+                PUZZLE_SCORING
+            )
+            .unwrap_err(),
+            RulesError::MutualBeating("Rock".to_string(), "Paper".to_string())
+        );
+        assert_eq!(
+            Rules::cyclic(0, PUZZLE_SCORING).unwrap_err(),
+            RulesError::NoMoves
+        );
+    }
+
+    #[test]
+    fn test_renamed() {
+        let rules = Rules::cyclic(2, PUZZLE_SCORING).unwrap();
+        let renamed = rules.clone().renamed(&["Heads", "Tails"]).unwrap();
+        assert_eq!(renamed.find("Tails"), Some(1));
+        assert_eq!(renamed.result(0, 1), rules.result(0, 1));
+        assert_eq!(
+            rules.clone().renamed(&["Heads"]).unwrap_err(),
+            RulesError::WrongNumberOfNames {
+                expected: 2,
+                found: 1
+            }
+        );
+        assert_eq!(
+            rules
+                .clone()
+                .renamed(&["Heads", "Tails", "Edge"])
+                .unwrap_err(),
+            RulesError::WrongNumberOfNames {
+                expected: 2,
+                found: 3
+            }
+        );
+        assert_eq!(
+            rules.renamed(&["Heads", "Heads"]).unwrap_err(),
+            RulesError::DuplicateMove("Heads".to_string())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_cyclic_results_are_symmetric(size in 1..40usize, a in 0..40usize, b in 0..40usize) {
+            let rules = Rules::cyclic(size, PUZZLE_SCORING).unwrap();
+            let (a, b) = (a % size, b % size);
+            let opposite = match rules.result(b, a) {
+                GameResult::Win => GameResult::Lose,
+                GameResult::Lose => GameResult::Win,
+                GameResult::Draw => GameResult::Draw,
+            };
+            prop_assert_eq!(rules.result(a, b), opposite);
+            prop_assert_eq!(rules.result(a, b) == GameResult::Draw, a == b || 2 * a.abs_diff(b) == size);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::cache::{self, Cache};
+use crate::error::{Error, ParseError};
+use crate::input::{self, InputSource};
+use crate::registry::Day;
+use crate::solution::{Answer, Solution, StreamingSolution};
+use std::any::Any;
+use std::io::BufRead;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/** Durations of the repeated runs of one phase: parsing, part 1 or part 2. */
+#[derive(Debug, Clone, Default)]
+pub struct Timing {
+    pub samples: Vec<Duration>,
+}
+
+impl Timing {
+    pub fn min(&self) -> Duration {
+        return self.samples.iter().min().copied().unwrap_or_default();
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut samples = self.samples.clone();
+        samples.sort();
+        let middle = samples.len() / 2;
+        return match samples.len() {
+            0 => Duration::ZERO,
+            length if length % 2 == 0 => (samples[middle - 1] + samples[middle]) / 2,
+            _ => samples[middle],
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: u8,
+    pub answer: Answer,
+    pub timing: Timing,
+}
+
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub parse: Timing,
+    pub parts: Vec<PartRun>,
+}
+
+fn timed<T>(timing: &mut Timing, function: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = function();
+    timing.samples.push(start.elapsed());
+    return result;
+}
+
+/**
+ * Parses the input once, and solves the requested parts from the parsed data.
+ * Everything is done `repeat` times to get more reliable timings.
+ * The input is normalized by the day first, which is not timed.
+ */
+pub fn run_day<S: Solution>(
+    input: &str,
+    parts: &[u8],
+    repeat: usize,
+) -> Result<DayRun, ParseError> {
+    let mut parse = Timing::default();
+    let mut part_runs = parts
+        .iter()
+        .map(|&part| PartRun {
+            part,
+            answer: Answer::Integer(0),
+            timing: Timing::default(),
+        })
+        .collect::<Vec<PartRun>>();
+
+    let input = S::normalize(input);
+    for _ in 0..repeat.max(1) {
+        let parsed = timed(&mut parse, || S::parse(&input))?;
+
+        for part_run in part_runs.iter_mut() {
+            part_run.answer = timed(&mut part_run.timing, || match part_run.part {
+                1 => S::part_1(&parsed),
+                2 => S::part_2(&parsed),
+                part => panic!("Part {} doesn't exist", part),
+            });
+        }
+    }
+
+    return Ok(DayRun {
+        day: S::DAY,
+        parse,
+        parts: part_runs,
+    });
+}
+
+/**
+ * Solves both parts in a single pass over the input, so it can't be repeated.
+ * Reading, parsing and solving are interleaved, so the whole pass is timed as the parsing.
+ */
+pub fn run_stream<S: StreamingSolution>(
+    reader: &mut dyn BufRead,
+    parts: &[u8],
+) -> Result<DayRun, Error> {
+    let mut parse = Timing::default();
+    let (part_1, part_2) = timed(&mut parse, || S::solve_stream(reader))?;
+
+    let part_runs = parts
+        .iter()
+        .map(|&part| PartRun {
+            part,
+            answer: match part {
+                1 => part_1.clone(),
+                2 => part_2.clone(),
+                part => panic!("Part {} doesn't exist", part),
+            },
+            timing: Timing::default(),
+        })
+        .collect();
+
+    return Ok(DayRun {
+        day: S::DAY,
+        parse,
+        parts: part_runs,
+    });
+}
+
+/** The message given to `panic!`, when there is one. */
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    return "no message".to_string();
+}
+
+fn panic_error(day: u8, message: String) -> Error {
+    return Error::Panic { day, message };
+}
+
+/** Calls `solve`, and gives a panic as the error of the day instead of unwinding further. */
+pub fn catch_panic<R>(day: u8, solve: impl FnOnce() -> Result<R, Error>) -> Result<R, Error> {
+    return catch_unwind(AssertUnwindSafe(solve))
+        .unwrap_or_else(|payload| Err(panic_error(day, panic_message(payload))));
+}
+
+/**
+ * Calls `solve` on every item using up to `jobs` threads, and returns the results
+ * in the order of the items. Every call is done on a single thread, so the timings
+ * measured inside it are not mixed with the other items.
+ * A panic only stops its item, which gives the message of the panic instead of a result.
+ */
+pub fn run_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    solve: impl Fn(&T) -> R + Sync,
+) -> Vec<Result<R, String>> {
+    let next_item = AtomicUsize::new(0);
+    let mut results = std::thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    // Take the next item until there are none left
+                    loop {
+                        let index = next_item.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            return results;
+                        };
+                        let result = catch_unwind(AssertUnwindSafe(|| solve(item)));
+                        results.push((index, result.map_err(panic_message)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        return workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Vec<(usize, Result<R, String>)>>();
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    return results.into_iter().map(|(_, result)| result).collect();
+}
+
+/**
+ * Like `run_parallel`, for items solving a day, such as the days or their examples.
+ * A panic gives the `Error::Panic` of the day of its item.
+ */
+pub fn solve_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    day_of: impl Fn(&T) -> u8,
+    solve: impl Fn(&T) -> Result<R, Error> + Sync,
+) -> Vec<Result<R, Error>> {
+    let solved = run_parallel(items, jobs, solve);
+    return items
+        .iter()
+        .zip(solved)
+        .map(|(item, solved)| {
+            solved.unwrap_or_else(|message| Err(panic_error(day_of(item), message)))
+        })
+        .collect();
+}
+
+/**
+ * Loads the input of a day, and solves the requested parts.
+ * With a cache, the previous run on the same input and with as many repeats is reused,
+ * timings included.
+ */
+pub fn solve_day(
+    day: &Day,
+    source: &InputSource,
+    inputs_dir: &Path,
+    parts: &[u8],
+    repeat: usize,
+    cache: Option<&Cache>,
+) -> Result<DayRun, Error> {
+    let data = input::load_input(day.day, source, inputs_dir)?;
+    let Some(cache) = cache else {
+        return Ok((day.run)(&data, parts, repeat)?);
+    };
+
+    let input_sha256 = cache::input_hash(&data);
+    if let Some(run) = cache.get(day.day, &input_sha256, parts, repeat) {
+        return Ok(run);
+    }
+    let run = (day.run)(&data, parts, repeat)?;
+    cache.insert(&input_sha256, &run);
+    return Ok(run);
+}
+
+/** The days without a streaming solver must have been filtered out before. */
+pub fn solve_day_stream(
+    day: &Day,
+    source: &InputSource,
+    inputs_dir: &Path,
+    parts: &[u8],
+) -> Result<DayRun, Error> {
+    let stream = day.stream.expect("The day has a streaming solver");
+    let mut reader = input::open_input(day.day, source, inputs_dir)?;
+    return stream(&mut reader, parts);
+}
+
+/** How `solve_days` solves every day. */
+pub struct RunOptions<'a> {
+    pub source: &'a InputSource,
+    pub inputs_dir: &'a Path,
+    pub parts: &'a [u8],
+    pub repeat: usize,
+    /** Solve while reading the input, which every day must support */
+    pub stream: bool,
+    pub jobs: usize,
+}
+
+/** Solves the days on up to `options.jobs` threads, and gives the results in the order of the days. */
+pub fn solve_days(
+    days: &[Day],
+    options: &RunOptions,
+    cache: Option<&Cache>,
+) -> Vec<Result<DayRun, Error>> {
+    return solve_parallel(
+        days,
+        options.jobs,
+        |day| day.day,
+        |day| match options.stream {
+            true => solve_day_stream(day, options.source, options.inputs_dir, options.parts),
+            false => solve_day(
+                day,
+                options.source,
+                options.inputs_dir,
+                options.parts,
+                options.repeat,
+                cache,
+            ),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(milliseconds: &[u64]) -> Timing {
+        return Timing {
+            samples: milliseconds
+                .iter()
+                .map(|ms| Duration::from_millis(*ms))
+                .collect(),
+        };
+    }
+
+    #[test]
+    fn test_timing() {
+        assert_eq!(timing(&[5, 1, 3]).min(), Duration::from_millis(1));
+        assert_eq!(timing(&[5, 1, 3]).median(), Duration::from_millis(3));
+        assert_eq!(timing(&[8, 1, 2, 4]).median(), Duration::from_millis(3));
+        assert_eq!(timing(&[]).median(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_parallel() {
+        let items = (0..20).collect::<Vec<u64>>();
+        for jobs in [1, 3, 50] {
+            let results = run_parallel(&items, jobs, |item| {
+                // The last items finish first
+                std::thread::sleep(Duration::from_millis(20 - item));
+                return item * 2;
+            });
+            assert_eq!(
+                results,
+                (0..20).map(|item| Ok(item * 2)).collect::<Vec<_>>()
+            );
+        }
+        assert!(run_parallel(&[] as &[u64], 4, |item| *item).is_empty());
+
+        // The other items still give their results
+        let results = run_parallel(&items, 3, |item| match item {
+            5 => panic!("Item {} is broken", item),
+            7 => std::panic::panic_any(42),
+            item => *item,
+        });
+        assert_eq!(results[4], Ok(4));
+        assert_eq!(results[5], Err("Item 5 is broken".to_string()));
+        assert_eq!(results[7], Err("no message".to_string()));
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 18);
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert!(matches!(catch_panic(3, || Ok(42)), Ok(42)));
+        let error = catch_panic::<u64>(3, || panic!("Unable to get character")).unwrap_err();
+        let Error::Panic { day, message } = &error else {
+            panic!("Expected a panic");
+        };
+        assert_eq!((*day, message.as_str()), (3, "Unable to get character"));
+        assert_eq!(error.to_string(), "Day 3 panicked: Unable to get character");
+    }
+
+    #[test]
+    fn test_solve_parallel() {
+        let items = [(1, 10), (2, 20), (3, 0)];
+        let results = solve_parallel(
+            &items,
+            2,
+            |(day, _)| *day,
+            |(day, value)| match value {
+                0 => panic!("Day {} has nothing", day),
+                value => Ok(value / 10),
+            },
+        );
+        assert!(matches!(results[0], Ok(1)));
+        assert!(matches!(results[1], Ok(2)));
+        let Err(Error::Panic { day, message }) = &results[2] else {
+            panic!("Expected a panic");
+        };
+        assert_eq!((*day, message.as_str()), (3, "Day 3 has nothing"));
+    }
+}
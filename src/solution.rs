@@ -0,0 +1,85 @@
+use crate::error::{Error, ParseError};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::BufRead;
+
+/** The answer of a part, puzzles expect either a number or a text. */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl Answer {
+    pub fn answer_type(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(integer) => write!(f, "{}", integer),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(integer: i64) -> Self {
+        Answer::Integer(integer)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(integer: usize) -> Self {
+        Answer::Integer(integer as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/** Implemented by every day, so the days can be run uniformly. */
+pub trait Solution {
+    const DAY: u8;
+
+    /** What the puzzle input is parsed into, shared by both parts */
+    type Parsed;
+
+    /**
+     * Repairs what `input::normalize` can't do for every day, before the parsing.
+     * By default the input is kept as it is.
+     */
+    fn normalize(input: &str) -> Cow<'_, str> {
+        return Cow::Borrowed(input);
+    }
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+    fn part_1(data: &Self::Parsed) -> Answer;
+    fn part_2(data: &Self::Parsed) -> Answer;
+}
+
+/**
+ * Implemented by the days that can also be solved while reading their input,
+ * with bounded memory, for inputs too large to be loaded at once.
+ * Their module registers them with `Day::streaming` so the runner knows.
+ */
+pub trait StreamingSolution: Solution {
+    /** Both parts, in a single pass over the input */
+    fn solve_stream(reader: &mut dyn BufRead) -> Result<(Answer, Answer), Error>;
+}
@@ -0,0 +1,116 @@
+use crate::input::{self, InputSource};
+use crate::registry::Day;
+use crate::runner;
+use crate::solution::Answer;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/** An input file of the watched day, with the answers of its last successful run. */
+struct WatchedFile {
+    name: &'static str,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    answers: HashMap<u8, Answer>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    return std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+}
+
+/** The answer of a part, and how it changed since the previous run. */
+pub fn describe_change(previous: Option<&Answer>, answer: &Answer) -> String {
+    return match previous {
+        None => answer.to_string(),
+        Some(previous) if previous == answer => format!("{} (unchanged)", answer),
+        Some(previous) => format!("{} (was {})", answer, previous),
+    };
+}
+
+fn run_file(day: &Day, file: &mut WatchedFile, inputs_dir: &Path) {
+    let source = InputSource::Path(file.path.clone());
+    // A half-saved file can make the day panic, which mustn't stop the watch
+    let solved = runner::catch_panic(day.day, || {
+        let data = input::load_input(day.day, &source, inputs_dir)?;
+        return Ok((day.run)(&data, &[1, 2], 1)?);
+    });
+
+    let run = match solved {
+        Ok(run) => run,
+        Err(error) => {
+            // Keep the previous answers, to compare with once it's fixed
+            eprintln!("{}", error);
+            return;
+        }
+    };
+    for part in run.parts {
+        let change = describe_change(file.answers.get(&part.part), &part.answer);
+        println!(
+            "Day {}, {}, part {}: {}",
+            day.day, file.name, part.part, change
+        );
+        file.answers.insert(part.part, part.answer);
+    }
+}
+
+/**
+ * Runs the day on its input, and on the example if there is one, then again every time
+ * one of these files changes. The modification times are polled, so no more than once
+ * per `interval`. The source of the day isn't watched, as the running binary can't pick
+ * up its changes without a rebuild. Never returns, stop it with Ctrl+C.
+ */
+pub fn watch(
+    day: &Day,
+    files: Vec<(&'static str, PathBuf)>,
+    inputs_dir: &Path,
+    interval: Duration,
+) -> ! {
+    let mut watched = files
+        .into_iter()
+        .map(|(name, path)| WatchedFile {
+            name,
+            path,
+            modified: None,
+            answers: HashMap::new(),
+        })
+        .collect::<Vec<WatchedFile>>();
+
+    for file in watched.iter_mut() {
+        file.modified = modified(&file.path);
+        run_file(day, file, inputs_dir);
+    }
+
+    loop {
+        std::thread::sleep(interval);
+        for file in watched.iter_mut() {
+            let modified = modified(&file.path);
+            if modified == file.modified {
+                continue;
+            }
+            file.modified = modified;
+            println!("{} changed", file.path.display());
+            run_file(day, file, inputs_dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_change() {
+        let answer = Answer::Integer(42);
+        assert_eq!(describe_change(None, &answer), "42");
+        assert_eq!(
+            describe_change(Some(&Answer::Integer(42)), &answer),
+            "42 (unchanged)"
+        );
+        assert_eq!(
+            describe_change(Some(&"CMZ".into()), &answer),
+            "42 (was CMZ)"
+        );
+    }
+}
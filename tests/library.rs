@@ -0,0 +1,125 @@
+use advent_of_code_2022_rust::answers::{self, ExpectedAnswers, Verdict};
+use advent_of_code_2022_rust::day_02::Action;
+use advent_of_code_2022_rust::day_04::{self, Day04, Range};
+use advent_of_code_2022_rust::day_07;
+use advent_of_code_2022_rust::day_09::{self, Direction, Instruction};
+use advent_of_code_2022_rust::error::Error;
+use advent_of_code_2022_rust::input::InputSource;
+use advent_of_code_2022_rust::registry;
+use advent_of_code_2022_rust::runner;
+use advent_of_code_2022_rust::runner::RunOptions;
+use advent_of_code_2022_rust::solution::Answer;
+use std::path::Path;
+
+const DAY_04_EXAMPLE: &str = include_str!("../inputs/examples/day_04_example.txt");
+const DAY_07_EXAMPLE: &str = include_str!("../inputs/examples/day_07_example.txt");
+
+#[test]
+fn test_parsed_types() {
+    let pairs = day_04::parse_input_data(DAY_04_EXAMPLE).unwrap();
+    assert_eq!(pairs[0].0, Range { start: 2, end: 4 });
+
+    let instructions = day_09::parse_input_data("R 4\nU 2").unwrap();
+    assert_eq!(
+        instructions[1],
+        Instruction {
+            direction: Direction::Up,
+            steps: 2
+        }
+    );
+
+    let filesystem = day_07::parse_input_data(DAY_07_EXAMPLE).unwrap();
+    assert_eq!(filesystem.files["/a/e/i"].size, 584);
+    assert!(filesystem.folders["/"].subfolders.contains("/a"));
+
+    assert_eq!("B".parse::<Action>(), Ok(Action::Paper));
+}
+
+#[test]
+fn test_runner() {
+    let run = runner::run_day::<Day04>(DAY_04_EXAMPLE, &[2], 1).unwrap();
+    assert_eq!(run.day, 4);
+    assert_eq!(run.parts[0].part, 2);
+    assert_eq!(run.parts[0].answer, Answer::Integer(4));
+
+    let day = registry::find(4).unwrap();
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/examples");
+    let source = InputSource::Path(examples_dir.join("day_04_example.txt"));
+    let run = runner::solve_day(&day, &source, &examples_dir, &[1, 2], 1, None).unwrap();
+    assert_eq!(run.parts[0].answer, Answer::Integer(2));
+    assert_eq!(run.parts[1].answer, Answer::Integer(4));
+
+    assert!(registry::find(25).is_none());
+}
+
+#[test]
+fn test_solve_days() {
+    let days = registry::select(Some(&(3..=4))).unwrap();
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs/examples");
+    let source = InputSource::Path(examples_dir.join("day_04_example.txt"));
+    for stream in [false, true] {
+        let options = RunOptions {
+            source: &source,
+            inputs_dir: &examples_dir,
+            parts: &[2],
+            repeat: 1,
+            stream,
+            jobs: 2,
+        };
+        let solved = runner::solve_days(&days[1..], &options, None);
+        assert_eq!(solved.len(), 1);
+        assert_eq!(
+            solved[0].as_ref().unwrap().parts[0].answer,
+            Answer::Integer(4)
+        );
+    }
+
+    // Every day is solved, even after an error
+    let options = RunOptions {
+        source: &InputSource::Default,
+        inputs_dir: Path::new("no_such_inputs_dir"),
+        parts: &[1, 2],
+        repeat: 1,
+        stream: false,
+        jobs: 1,
+    };
+    let solved = runner::solve_days(&days, &options, None);
+    assert_eq!(solved.len(), 2);
+    assert!(solved
+        .iter()
+        .all(|solved| matches!(solved, Err(Error::Input(_)))));
+}
+
+#[test]
+fn test_verify() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let expected = ExpectedAnswers::load(&root.join("answers.toml")).unwrap();
+    let verifications = answers::verify_inputs(&expected, &root.join("inputs"), 4, None);
+    assert_eq!(verifications.len(), registry::days().len());
+    assert_eq!(verifications[0].name, "Day 1");
+    for verification in verifications.iter() {
+        assert_eq!(verification.failures(), 0, "{}", verification.name);
+    }
+
+    let examples_dir = root.join("inputs/examples");
+    let examples = answers::load_examples(&examples_dir).unwrap();
+    let verifications = answers::verify_examples(&examples, &examples_dir, 4, None);
+    assert_eq!(verifications.len(), examples.len());
+    for verification in verifications.iter() {
+        let parts = verification.parts.as_ref().unwrap();
+        assert!(parts.iter().all(|(_, verdict)| *verdict == Verdict::Pass));
+    }
+
+    // Without answers, the days are solved but nothing fails
+    let verifications =
+        answers::verify_inputs(&ExpectedAnswers::default(), &root.join("inputs"), 4, None);
+    let parts = verifications[0].parts.as_ref().unwrap();
+    assert!(matches!(parts[0], (1, Verdict::Unknown(_))));
+    assert_eq!(
+        verifications
+            .iter()
+            .map(|verification| verification.failures())
+            .sum::<usize>(),
+        0
+    );
+}